@@ -1,14 +1,23 @@
 //! App module - main application logic and UI
 
 use eframe::egui;
+use std::collections::{HashSet, VecDeque};
 use std::rc::Rc;
 use std::sync::OnceLock;
-use crate::document::Document;
+use crate::document::{Document, ReplaceScope};
 use crate::ui::{render_cell, CellColors, AboutDialog};
-use crate::settings::{ExportSettings, CsvEncoding, ThemeMode, AeKeyframeVersion};
+use crate::ui::cell::{font_size_for_row, BASE_COL_WIDTH, BASE_ROW_HEIGHT};
+use crate::settings::{ExportSettings, CsvEncoding, CsvExportMode, ThemeMode, AeKeyframeVersion, MAX_GRID_ZOOM_PERCENT, MIN_GRID_ZOOM_PERCENT};
 use sts_rust::TimeSheet;
 use sts_rust::models::timesheet::CellValue;
 
+// 最大同时打开的文档数量
+const MAX_DOCUMENTS: usize = 100;
+// 可恢复的最近关闭文档数量
+const MAX_RECENTLY_CLOSED: usize = 10;
+// 缩放停止多久后保存设置
+const ZOOM_SAVE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
 pub struct StsApp {
     pub documents: Vec<Document>,
     pub next_doc_id: usize,
@@ -24,6 +33,7 @@ pub struct StsApp {
     pub new_seconds: u32,
     pub new_frames: u32,
     pub error_message: Option<String>,
+    pub warning_message: Option<String>,  // 导入时的非致命警告（黄色显示）
     // 应用程序关闭状态
     pub show_exit_dialog: bool,
     pub allowed_to_close: bool,
@@ -32,11 +42,17 @@ pub struct StsApp {
     pub show_settings_dialog: bool,
     pub temp_csv_header_name: String,
     pub temp_csv_encoding: usize, // 0: UTF-8, 1: GB2312, 2: Shift-JIS
+    pub temp_csv_delimiter: u8,
+    pub temp_csv_export_mode: CsvExportMode,
     pub temp_auto_save_enabled: bool,
     pub temp_theme_mode: ThemeMode,
     pub temp_ae_keyframe_version: usize, // 0: 6.0, 1: 7.0, 2: 8.0, 3: 9.0
     // 关于对话框
     pub about_dialog: AboutDialog,
+    // 最近关闭的文档（表格数据、原文件路径和是否有未保存修改），用于 Ctrl+Shift+T 恢复
+    pub recently_closed: VecDeque<(TimeSheet, Option<String>, bool)>,
+    // 最近一次 Ctrl+滚轮 缩放的时间，缩放停止后才保存设置
+    pub zoom_changed_at: Option<std::time::Instant>,
 }
 
 impl Default for StsApp {
@@ -56,29 +72,37 @@ impl Default for StsApp {
             new_dialog_focus_name: false,
             closing_doc_id: None,
             new_name: "sheet1".to_string(),
-            new_framerate: 24,
-            new_layer_count: 12,
-            new_frames_per_page: 144,
-            new_seconds: 6,
-            new_frames: 0,
+            // 新建对话框使用上次的设置
+            new_framerate: settings.new_framerate,
+            new_layer_count: settings.new_layer_count,
+            new_frames_per_page: settings.new_frames_per_page,
+            new_seconds: settings.new_seconds,
+            new_frames: settings.new_frames.min(settings.new_framerate - 1),
             error_message: None,
+            warning_message: None,
             show_exit_dialog: false,
             allowed_to_close: false,
             temp_csv_header_name: settings.csv_header_name.clone(),
             temp_csv_encoding: temp_encoding,
+            temp_csv_delimiter: settings.csv_delimiter,
+            temp_csv_export_mode: settings.csv_export_mode,
             temp_auto_save_enabled: settings.auto_save_enabled,
             temp_theme_mode: settings.theme_mode,
             temp_ae_keyframe_version: settings.ae_keyframe_version.index(),
             settings,
             show_settings_dialog: false,
             about_dialog: AboutDialog::default(),
+            recently_closed: VecDeque::new(),
+            zoom_changed_at: None,
         }
     }
 }
 
 impl StsApp {
     pub fn create_new_document(&mut self) {
-        let total_frames = (self.new_seconds * self.new_framerate + self.new_frames) as usize;
+        // 超出帧数上限的表格无法重新打开，按上限截断
+        let total_frames = ((self.new_seconds * self.new_framerate + self.new_frames) as usize)
+            .clamp(1, sts_rust::limits::max_frames());
 
         let mut ts = TimeSheet::new(
             self.new_name.clone(),
@@ -86,18 +110,121 @@ impl StsApp {
             self.new_layer_count,
             self.new_frames_per_page,
         );
-        ts.ensure_frames(total_frames.max(1));
+        ts.ensure_frames(total_frames);
 
         let doc = Document::new(self.next_doc_id, ts, None);
         self.next_doc_id += 1;
         self.documents.push(doc);
         self.show_new_dialog = false;
+
+        // 记住本次的新建参数
+        self.settings.new_framerate = self.new_framerate;
+        self.settings.new_layer_count = self.new_layer_count;
+        self.settings.new_frames_per_page = self.new_frames_per_page;
+        self.settings.new_seconds = self.new_seconds;
+        self.settings.new_frames = self.new_frames;
+        if let Err(e) = self.settings.save_to_registry() {
+            self.error_message = Some(format!("Failed to save settings: {}", e));
+        }
+    }
+
+    /// 复制文档（不带文件路径、撤销记录和剪贴板）
+    pub fn duplicate_document(&mut self, doc_id: usize) {
+        if self.documents.len() >= MAX_DOCUMENTS {
+            self.error_message = Some(format!("Too many documents open (max: {}). Please close some documents first.", MAX_DOCUMENTS));
+            return;
+        }
+        let Some(doc) = self.documents.iter().find(|d| d.id == doc_id) else {
+            return;
+        };
+
+        let mut ts = (*doc.timesheet).clone();
+        ts.name = format!("{} (copy)", ts.name);
+        let copy = Document::new(self.next_doc_id, ts, None);
+        self.active_doc_id = Some(copy.id);
+        self.next_doc_id += 1;
+        self.documents.push(copy);
+    }
+
+    /// 移除已关闭的文档，并记录到最近关闭列表
+    fn remove_closed_documents(&mut self) {
+        let (closed, open): (Vec<Document>, Vec<Document>) = std::mem::take(&mut self.documents)
+            .into_iter()
+            .partition(|d| !d.is_open);
+        self.documents = open;
+
+        if self.remember_window_layouts(&closed) {
+            let _ = self.settings.save_to_registry();
+        }
+
+        for doc in closed {
+            if self.recently_closed.len() >= MAX_RECENTLY_CLOSED {
+                self.recently_closed.pop_front();
+            }
+            self.recently_closed.push_back((*doc.timesheet, doc.file_path.map(String::from), doc.is_modified));
+        }
+    }
+
+    /// 按文件路径记录文档窗口的位置和尺寸，返回是否有更新
+    fn remember_window_layouts(&mut self, docs: &[Document]) -> bool {
+        let mut changed = false;
+        for doc in docs {
+            if let (Some(path), Some((pos, size))) = (doc.file_path.as_deref(), doc.window_layout) {
+                self.settings.remember_window_layout(path, [pos.x, pos.y, size.x, size.y]);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// 恢复最近关闭的文档
+    pub fn reopen_closed_document(&mut self) {
+        if self.documents.len() >= MAX_DOCUMENTS {
+            self.error_message = Some(format!("Too many documents open (max: {}). Please close some documents first.", MAX_DOCUMENTS));
+            return;
+        }
+        let Some((ts, file_path, is_modified)) = self.recently_closed.pop_back() else {
+            return;
+        };
+
+        // 同一文件已重新打开时不再关联路径，避免两个窗口写同一文件
+        let file_path = file_path.filter(|path| {
+            !self.documents.iter().any(|d| d.file_path.as_deref() == Some(path.as_str()))
+        });
+        let mut doc = Document::new(self.next_doc_id, ts, file_path);
+        // 关闭时放弃的修改仍未保存，不能显示为已保存，否则下次保存会覆盖磁盘上的文件
+        doc.is_modified = is_modified;
+        self.active_doc_id = Some(doc.id);
+        self.next_doc_id += 1;
+        self.documents.push(doc);
+    }
+
+    /// 将文档按页拆分，每页作为新文档打开
+    pub fn split_document_by_page(&mut self, doc_id: usize) {
+        let Some(doc) = self.documents.iter().find(|d| d.id == doc_id) else {
+            return;
+        };
+        let pages = doc.timesheet.split_by_page();
+
+        if self.documents.len() + pages.len() > MAX_DOCUMENTS {
+            self.error_message = Some(format!("Too many documents open (max: {}). Please close some documents first.", MAX_DOCUMENTS));
+            return;
+        }
+
+        let page_count = pages.len();
+        for page in pages {
+            let doc = Document::new(self.next_doc_id, page, None);
+            self.next_doc_id += 1;
+            self.documents.push(doc);
+        }
+        self.error_message = Some(format!("Split into {} page(s)", page_count));
     }
 
     /// Load a file from the given path
     fn load_file_from_path(&mut self, path_str: &str) {
+        self.warning_message = None;
+
         // 限制最大文档数量
-        const MAX_DOCUMENTS: usize = 100;
         if self.documents.len() >= MAX_DOCUMENTS {
             self.error_message = Some(format!("Too many documents open (max: {}). Please close some documents first.", MAX_DOCUMENTS));
             return;
@@ -133,17 +260,17 @@ impl StsApp {
                 }
             }
             "xdts" => {
-                match sts_rust::parse_xdts_file(path_str) {
-                    Ok(timesheets) => {
-                        if timesheets.is_empty() {
-                            self.error_message = Some("No timesheets found in XDTS file".to_string());
+                match sts_rust::parse_xdts_file_with_report(path_str) {
+                    Ok(result) => {
+                        if result.timesheets.is_empty() {
+                            self.error_message = Some(format!("No timesheets found in XDTS file{}", skipped_suffix(&result.skipped)));
                         } else {
-                            for ts in timesheets {
+                            self.error_message = multi_sheet_report(result.timesheets.len(), &result.skipped);
+                            for ts in result.timesheets {
                                 let doc = Document::new(self.next_doc_id, ts, None);
                                 self.next_doc_id += 1;
                                 self.documents.push(doc);
                             }
-                            self.error_message = None;
                         }
                     }
                     Err(e) => {
@@ -155,17 +282,20 @@ impl StsApp {
                 match sts_rust::parse_tdts_file(path_str) {
                     Ok(result) => {
                         if result.timesheets.is_empty() {
-                            self.error_message = Some("No timesheets found in TDTS file".to_string());
+                            self.error_message = Some(format!("No timesheets found in TDTS file{}", skipped_suffix(&result.skipped)));
                         } else {
+                            self.error_message = multi_sheet_report(result.timesheets.len(), &result.skipped);
                             for ts in result.timesheets {
                                 let doc = Document::new(self.next_doc_id, ts, None);
                                 self.next_doc_id += 1;
                                 self.documents.push(doc);
                             }
                             if !result.warnings.is_empty() {
-                                self.error_message = Some(format!("Warning: {}", result.warnings.join(", ")));
-                            } else {
-                                self.error_message = None;
+                                self.warning_message = Some(format!(
+                                    "{} warning(s) while importing: {}",
+                                    result.warnings.len(),
+                                    result.warnings.join("; ")
+                                ));
                             }
                         }
                     }
@@ -174,8 +304,21 @@ impl StsApp {
                     }
                 }
             }
-            "csv" => {
-                match sts_rust::parse_csv_file(path_str) {
+            "json" => {
+                match sts_rust::parse_json_file(path_str) {
+                    Ok(ts) => {
+                        let doc = Document::new(self.next_doc_id, ts, None);
+                        self.next_doc_id += 1;
+                        self.documents.push(doc);
+                        self.error_message = None;
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to open: {}", e));
+                    }
+                }
+            }
+            "txt" => {
+                match sts_rust::parse_ae_keyframe_file(path_str) {
                     Ok(ts) => {
                         let doc = Document::new(self.next_doc_id, ts, None);
                         self.next_doc_id += 1;
@@ -187,10 +330,31 @@ impl StsApp {
                     }
                 }
             }
+            "csv" => {
+                match sts_rust::parse_csv_file_with_warnings(path_str) {
+                    Ok(result) => {
+                        let doc = Document::new(self.next_doc_id, result.timesheet, None);
+                        self.next_doc_id += 1;
+                        self.documents.push(doc);
+                        self.error_message = None;
+                        if !result.warnings.is_empty() {
+                            self.warning_message = Some(format!(
+                                "{} warning(s) while importing: {}",
+                                result.warnings.len(),
+                                result.warnings.join("; ")
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to open: {}", e));
+                    }
+                }
+            }
             "sxf" => {
                 // Use new SXF parser that handles multi-section format
-                match sts_rust::parse_sxf_groups(path_str) {
-                    Ok(groups) => {
+                match sts_rust::parse_sxf_groups_with_warnings(path_str) {
+                    Ok(result) => {
+                        let groups = result.groups;
                         // Convert groups to TimeSheet for display
                         let filename = std::path::Path::new(path_str)
                             .file_name()
@@ -203,6 +367,13 @@ impl StsApp {
                                 self.next_doc_id += 1;
                                 self.documents.push(doc);
                                 self.error_message = None;
+                                if !result.warnings.is_empty() {
+                                    self.warning_message = Some(format!(
+                                        "{} warning(s) while importing: {}",
+                                        result.warnings.len(),
+                                        result.warnings.join("; ")
+                                    ));
+                                }
                             }
                             Err(e) => {
                                 self.error_message = Some(format!("Failed to convert SXF: {}", e));
@@ -222,12 +393,14 @@ impl StsApp {
 
     pub fn open_document(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
-            .add_filter("All Supported", &["sts", "xdts", "tdts", "csv", "sxf"])
+            .add_filter("All Supported", &["sts", "xdts", "tdts", "csv", "sxf", "txt", "json"])
             .add_filter("STS Files", &["sts"])
             .add_filter("XDTS Files", &["xdts"])
             .add_filter("TDTS Files", &["tdts"])
             .add_filter("CSV Files", &["csv"])
             .add_filter("SXF Files", &["sxf"])
+            .add_filter("AE Keyframe Data", &["txt"])
+            .add_filter("JSON Files", &["json"])
             .pick_file()
         {
             let path_str = path.to_str().unwrap();
@@ -235,6 +408,51 @@ impl StsApp {
         }
     }
 
+    /// 打开拖入的多个文件：逐个加载（失败不影响其余文件），跳过重复和已打开的路径，
+    /// 最后显示汇总。单个文件时与直接打开相同
+    fn load_dropped_files(&mut self, paths: &[String]) {
+        if let [path] = paths {
+            self.load_file_from_path(path);
+            return;
+        }
+
+        let mut seen = HashSet::new();
+        let mut opened = 0;
+        let mut already_open = 0;
+        let mut failed = Vec::new();
+        let mut warnings = Vec::new();
+        for path in paths {
+            let is_open = self.documents.iter().any(|d| d.file_path.as_deref() == Some(path.as_str()));
+            if !seen.insert(path.as_str()) || is_open {
+                already_open += 1;
+                continue;
+            }
+
+            let doc_count = self.documents.len();
+            self.load_file_from_path(path);
+            if self.documents.len() > doc_count {
+                opened += 1;
+                warnings.extend(self.warning_message.take());
+            } else {
+                let file_name = std::path::Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                failed.push(file_name.to_string());
+            }
+        }
+
+        let mut summary = format!("Opened {}", opened);
+        if already_open > 0 {
+            summary.push_str(&format!(", skipped {} already open", already_open));
+        }
+        if !failed.is_empty() {
+            summary.push_str(&format!(", failed {}: {}", failed.len(), failed.join(", ")));
+        }
+        self.error_message = Some(summary);
+        self.warning_message = (!warnings.is_empty()).then(|| warnings.join("; "));
+    }
+
     pub fn save_document(&mut self, doc_id: usize) {
         if let Some(doc) = self.documents.iter_mut().find(|d| d.id == doc_id) {
             if doc.file_path.is_some() {
@@ -242,6 +460,7 @@ impl StsApp {
                     self.error_message = Some(e);
                 } else {
                     self.error_message = None;
+                    self.warning_message = sts_rust::sts_suffix_warning(&doc.timesheet);
                 }
             } else {
                 self.save_document_as(doc_id);
@@ -266,15 +485,24 @@ impl StsApp {
                     self.error_message = Some(e);
                 } else {
                     self.error_message = None;
+                    self.warning_message = sts_rust::sts_suffix_warning(&doc.timesheet);
                 }
             }
         }
     }
 
     pub fn export_to_csv(&mut self, doc_id: usize) {
+        self.export_range_to_csv(doc_id, None);
+    }
+
+    /// 导出 CSV；range 为 (min_layer, min_frame, max_layer, max_frame) 时只导出该选区，帧号从 1 重新开始
+    pub fn export_range_to_csv(&mut self, doc_id: usize, range: Option<(usize, usize, usize, usize)>) {
         let default_name = self.documents.iter()
             .find(|d| d.id == doc_id)
-            .map(|d| format!("{}.csv", d.timesheet.name))
+            .map(|d| match range {
+                Some(_) => format!("{}_selection.csv", d.timesheet.name),
+                None => format!("{}.csv", d.timesheet.name),
+            })
             .unwrap_or_else(|| "export.csv".to_string());
 
         if let Some(path) = rfd::FileDialog::new()
@@ -284,11 +512,21 @@ impl StsApp {
         {
             let path_str = path.to_str().unwrap();
             if let Some(doc) = self.documents.iter().find(|d| d.id == doc_id) {
+                let selection;
+                let timesheet = match range {
+                    Some((min_layer, min_frame, max_layer, max_frame)) => {
+                        selection = doc.timesheet.extract_range(min_layer, min_frame, max_layer, max_frame);
+                        &selection
+                    }
+                    None => &doc.timesheet,
+                };
                 match sts_rust::write_csv_file_with_options(
-                    &doc.timesheet,
+                    timesheet,
                     path_str,
                     &self.settings.csv_header_name,
                     self.settings.csv_encoding,
+                    self.settings.csv_delimiter,
+                    self.settings.csv_export_mode,
                 ) {
                     Ok(_) => {
                         self.error_message = Some(format!("Exported to CSV: {}", path_str));
@@ -301,6 +539,31 @@ impl StsApp {
         }
     }
 
+    pub fn export_to_ae_jsx(&mut self, doc_id: usize) {
+        let default_name = self.documents.iter()
+            .find(|d| d.id == doc_id)
+            .map(|d| format!("{}.jsx", d.timesheet.name))
+            .unwrap_or_else(|| "export.jsx".to_string());
+
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("After Effects Script", &["jsx"])
+            .set_file_name(&default_name)
+            .save_file()
+        {
+            let path_str = path.to_str().unwrap();
+            if let Some(doc) = self.documents.iter().find(|d| d.id == doc_id) {
+                match sts_rust::write_ae_jsx(&doc.timesheet, path_str) {
+                    Ok(_) => {
+                        self.error_message = Some(format!("Exported AE script: {}", path_str));
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to export AE script: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
     /// Auto-save document if auto-save is enabled and document has a file path
     fn auto_save_document(&mut self, doc_idx: usize) {
         if self.settings.auto_save_enabled {
@@ -361,6 +624,8 @@ impl eframe::App for StsApp {
             }
         }
 
+        self.save_zoom_when_settled(ctx);
+
         // 退出确认对话框
         if self.show_exit_dialog {
             let unsaved_docs: Vec<String> = self.documents.iter()
@@ -446,6 +711,7 @@ impl eframe::App for StsApp {
         }
 
         // 全局快捷键 (使用 command 修饰符：macOS 上为 Cmd，Windows/Linux 上为 Ctrl)
+        let mut should_reopen = false;
         ctx.input(|i| {
             if i.modifiers.command && i.key_pressed(egui::Key::N) {
                 self.show_new_dialog = true;
@@ -454,20 +720,24 @@ impl eframe::App for StsApp {
             if i.modifiers.command && i.key_pressed(egui::Key::O) {
                 self.open_document();
             }
+            // Ctrl+Shift+T 恢复最近关闭的文档
+            if i.modifiers.command && i.modifiers.shift && i.key_pressed(egui::Key::T) {
+                should_reopen = true;
+            }
         });
+        if should_reopen {
+            self.reopen_closed_document();
+        }
 
         // 拖拽文件支持
-        ctx.input(|i| {
-            if !i.raw.dropped_files.is_empty() {
-                for file in &i.raw.dropped_files {
-                    if let Some(path) = &file.path {
-                        if let Some(path_str) = path.to_str() {
-                            self.load_file_from_path(path_str);
-                        }
-                    }
-                }
-            }
+        let dropped: Vec<String> = ctx.input(|i| {
+            i.raw.dropped_files.iter()
+                .filter_map(|file| file.path.as_ref()?.to_str().map(str::to_string))
+                .collect()
         });
+        if !dropped.is_empty() {
+            self.load_dropped_files(&dropped);
+        }
 
         // 菜单栏
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
@@ -487,13 +757,118 @@ impl eframe::App for StsApp {
 
                     ui.separator();
 
+                    let active_id = self.active_doc_id.filter(|id| self.documents.iter().any(|d| d.id == *id));
+                    if ui.add_enabled(active_id.is_some(), egui::Button::new(format!("Save As... ({shortcut_modifier}Shift+S)"))).clicked() {
+                        if let Some(id) = active_id {
+                            self.save_document_as(id);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_id.is_some(), egui::Button::new(format!("Export CSV... ({shortcut_modifier}E)"))).clicked() {
+                        if let Some(id) = active_id {
+                            self.export_to_csv(id);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_id.is_some(), egui::Button::new("Export AE Script...")).clicked() {
+                        if let Some(id) = active_id {
+                            self.export_to_ae_jsx(id);
+                        }
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
+                    if ui.add_enabled(active_id.is_some(), egui::Button::new("Duplicate")).clicked() {
+                        if let Some(id) = active_id {
+                            self.duplicate_document(id);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_id.is_some(), egui::Button::new("Split by Page")).clicked() {
+                        if let Some(id) = active_id {
+                            self.split_document_by_page(id);
+                        }
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
+                    let can_reopen = !self.recently_closed.is_empty();
+                    if ui.add_enabled(can_reopen, egui::Button::new(format!("Reopen Closed ({shortcut_modifier}Shift+T)"))).clicked() {
+                        self.reopen_closed_document();
+                        ui.close_menu();
+                    }
+
                     if ui.button("Close All").clicked() {
-                        self.documents.clear();
+                        for doc in &mut self.documents {
+                            doc.is_open = false;
+                        }
+                        self.remove_closed_documents();
                         ui.close_menu();
                     }
                 });
 
                 ui.menu_button("Edit", |ui| {
+                    let shortcut_modifier = if cfg!(target_os = "macos") { "⌘" } else { "Ctrl+" };
+                    let mut active_doc = self.active_doc_id
+                        .and_then(|id| self.documents.iter_mut().find(|d| d.id == id));
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new(format!("Select All ({shortcut_modifier}A)"))).clicked() {
+                        if let Some(doc) = active_doc.as_mut() {
+                            doc.select_all();
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new(format!("Select Column ({shortcut_modifier}Shift+A)"))).clicked() {
+                        if let Some(doc) = active_doc.as_mut() {
+                            if let Some((layer, _)) = doc.selection_state.selected_cell {
+                                doc.select_layer(layer);
+                            }
+                        }
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new("Clear All...")).clicked() {
+                        if let Some(doc) = active_doc.as_mut() {
+                            doc.clear_dialog_open = true;
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new("Replace Values...")).clicked() {
+                        if let Some(doc) = active_doc.as_mut() {
+                            // 有多格选区时默认只替换选区，否则替换整表
+                            doc.replace_dialog.scope = match doc.get_selection_range() {
+                                Some((min_layer, min_frame, max_layer, max_frame))
+                                    if min_layer != max_layer || min_frame != max_frame => ReplaceScope::Selection,
+                                _ => ReplaceScope::Sheet,
+                            };
+                            doc.replace_dialog.open = true;
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new("Offset Numbers...")).clicked() {
+                        if let Some(doc) = active_doc.as_mut() {
+                            doc.offset_dialog.scope = match doc.get_selection_range() {
+                                Some((min_layer, min_frame, max_layer, max_frame))
+                                    if min_layer != max_layer || min_frame != max_frame => ReplaceScope::Selection,
+                                _ => ReplaceScope::Sheet,
+                            };
+                            doc.offset_dialog.open = true;
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(active_doc.is_some(), egui::Button::new("Set Duration...")).clicked() {
+                        if let Some(doc) = active_doc {
+                            let (seconds, frames) = doc.timesheet.frame_to_timecode(doc.timesheet.total_frames());
+                            doc.duration_dialog.seconds = seconds;
+                            doc.duration_dialog.frames = frames;
+                            doc.duration_dialog.open = true;
+                        }
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
                     if ui.button("Settings...").clicked() {
                         // 初始化临时设置值
                         self.temp_csv_header_name = self.settings.csv_header_name.clone();
@@ -502,6 +877,8 @@ impl eframe::App for StsApp {
                             CsvEncoding::Gb2312 => 1,
                             CsvEncoding::ShiftJis => 2,
                         };
+                        self.temp_csv_delimiter = self.settings.csv_delimiter;
+                        self.temp_csv_export_mode = self.settings.csv_export_mode;
                         self.temp_auto_save_enabled = self.settings.auto_save_enabled;
                         self.temp_theme_mode = self.settings.theme_mode;
                         self.show_settings_dialog = true;
@@ -567,6 +944,31 @@ impl eframe::App for StsApp {
                             });
                     });
 
+                    ui.add_space(5.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Delimiter:");
+                        egui::ComboBox::from_id_salt("csv_delimiter")
+                            .selected_text(match self.temp_csv_delimiter {
+                                b';' => "Semicolon (;)",
+                                b'\t' => "Tab",
+                                _ => "Comma (,)",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.temp_csv_delimiter, b',', "Comma (,)");
+                                ui.selectable_value(&mut self.temp_csv_delimiter, b';', "Semicolon (;)");
+                                ui.selectable_value(&mut self.temp_csv_delimiter, b'\t', "Tab");
+                            });
+                    });
+
+                    ui.add_space(5.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Mode:");
+                        ui.radio_value(&mut self.temp_csv_export_mode, CsvExportMode::KeyframesOnly, "Keyframes only");
+                        ui.radio_value(&mut self.temp_csv_export_mode, CsvExportMode::FullFrames, "Full frames");
+                    });
+
                     ui.add_space(15.0);
                     ui.heading("General");
                     ui.add_space(5.0);
@@ -634,6 +1036,8 @@ impl eframe::App for StsApp {
                     2 => CsvEncoding::ShiftJis,
                     _ => CsvEncoding::Gb2312,
                 };
+                self.settings.csv_delimiter = self.temp_csv_delimiter;
+                self.settings.csv_export_mode = self.temp_csv_export_mode;
                 self.settings.auto_save_enabled = self.temp_auto_save_enabled;
                 self.settings.theme_mode = self.temp_theme_mode;
                 self.settings.ae_keyframe_version = AeKeyframeVersion::from_index(self.temp_ae_keyframe_version);
@@ -740,6 +1144,19 @@ impl eframe::App for StsApp {
                         ui.label(buf2.format(total_pages));
                     });
 
+                    // 超出帧数上限时按上限创建；STS 帧数为 u16，超出时仍允许创建（可导出 CSV），但提前提示
+                    if total_frames as usize > sts_rust::limits::max_frames() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ Exceeds the limit of {} frames; the sheet will be cut to that length", sts_rust::limits::max_frames()),
+                        );
+                    } else if total_frames as usize > sts_rust::STS_MAX_FRAMES {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ Exceeds STS limit of {} frames; use Export CSV to keep every frame", sts_rust::STS_MAX_FRAMES),
+                        );
+                    }
+
                     ui.separator();
 
                     let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
@@ -750,10 +1167,16 @@ impl eframe::App for StsApp {
             return;
         }
 
-        // 错误消息
-        if let Some(msg) = &self.error_message {
+        // 错误 / 警告消息
+        if self.error_message.is_some() || self.warning_message.is_some() {
             egui::TopBottomPanel::bottom("error_panel").show(ctx, |ui| {
-                ui.colored_label(egui::Color32::RED, msg);
+                if let Some(msg) = &self.error_message {
+                    ui.colored_label(egui::Color32::RED, msg);
+                }
+                if let Some(msg) = &self.warning_message {
+                    let warn_color = ui.visuals().warn_fg_color;
+                    ui.colored_label(warn_color, msg);
+                }
             });
         }
 
@@ -783,15 +1206,26 @@ impl eframe::App for StsApp {
 
             let mut window_open = true;
 
-            let _window_resp = egui::Window::new(&window_title)
+            // 有保存的布局时恢复位置和尺寸，否则使用默认尺寸
+            let saved_layout = self.documents[doc_idx].file_path.as_deref()
+                .and_then(|path| self.settings.window_layout(path));
+            let mut window = egui::Window::new(&window_title)
                 .id(egui::Id::new(format!("doc_{}", doc_id_val)))
                 .open(&mut window_open)
                 .resizable(true)
                 .min_width(400.0)
-                .min_height(300.0)
-                .default_width(800.0)
-                .default_height(600.0)
+                .min_height(300.0);
+            window = match saved_layout {
+                Some([x, y, width, height]) => window
+                    .default_pos(egui::pos2(x, y))
+                    .default_size(egui::vec2(width, height)),
+                None => window.default_width(800.0).default_height(600.0),
+            };
+
+            let mut content_size = None;
+            let window_resp = window
                 .show(ctx, |ui| {
+                    content_size = Some(ui.available_size());
                     // 使用 ScrollArea 包裹所有内容，防止内容大小影响窗口
                     egui::ScrollArea::both()
                         .auto_shrink([false, false])
@@ -801,29 +1235,35 @@ impl eframe::App for StsApp {
                                 if ui.button("Save (Ctrl+S)").clicked() {
                                     docs_to_save.push(doc_id_val);
                                 }
-                                if ui.button("Save As...").clicked() {
+                                if ui.button("Save As...").on_hover_text("Ctrl+Shift+S").clicked() {
                                     docs_to_save_as.push(doc_id_val);
                                 }
                                 ui.separator();
-                                if ui.button("Export CSV...").clicked() {
+                                if ui.button("Export CSV...").on_hover_text("Ctrl+E").clicked() {
                                     self.export_to_csv(doc_id_val);
                                 }
+                                ui.separator();
+                                ui.toggle_value(&mut self.documents[doc_idx].show_stats, "Stats");
+                                ui.toggle_value(&mut self.documents[doc_idx].color_by_number, "Color by #")
+                                    .on_hover_text("Tint cells by drawing number to spot cycles");
                             });
 
                             ui.separator();
 
                             // 文档信息
-                            let (name, total_frames, cursor_info) = {
+                            let (name, total_frames, total_timecode, cursor_info) = {
                                 let doc = &self.documents[doc_idx];
                                 let cursor = if let Some((layer, frame)) = doc.selection_state.selected_cell {
                                     let layer_name = doc.timesheet.layer_names.get(layer)
                                         .map(|s| s.as_str())
                                         .unwrap_or("?");
-                                    Some(format!("{} {}K", layer_name, frame + 1))
+                                    // 第 24K 结束时为 1+00（24fps）
+                                    Some(format!("{} {}K ({})", layer_name, frame + 1, doc.timesheet.format_timecode(frame + 1)))
                                 } else {
                                     None
                                 };
-                                (doc.timesheet.name.clone(), doc.timesheet.total_frames(), cursor)
+                                let total_frames = doc.timesheet.total_frames();
+                                (doc.timesheet.name.clone(), total_frames, doc.timesheet.format_timecode(total_frames), cursor)
                             };
 
                             ui.horizontal(|ui| {
@@ -832,10 +1272,29 @@ impl eframe::App for StsApp {
                                 ui.label("Total Frames:");
                                 let mut frames_buf = itoa::Buffer::new();
                                 ui.label(frames_buf.format(total_frames));
+                                ui.label(format!("({})", total_timecode));
                                 if let Some(ref cursor) = cursor_info {
                                     ui.separator();
                                     ui.label(cursor);
                                 }
+                                ui.separator();
+
+                                // 跳转框：输入 "p3" 跳到第 3 页，数字跳到对应帧
+                                ui.label("Go to:");
+                                let doc = &mut self.documents[doc_idx];
+                                let goto_resp = ui.add(
+                                    egui::TextEdit::singleline(&mut doc.goto_text)
+                                        .desired_width(60.0)
+                                        .hint_text("p3 / 120"),
+                                );
+                                // 失去焦点的这一帧也要屏蔽快捷键，否则 Enter 会同时触发表格的 Enter 填充
+                                doc.goto_focused = goto_resp.has_focus() || goto_resp.lost_focus();
+                                if goto_resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                    let input = std::mem::take(&mut doc.goto_text);
+                                    if let Err(e) = doc.go_to(&input) {
+                                        self.error_message = Some(format!("{}: {}", e, input.trim()));
+                                    }
+                                }
                             });
 
                             ui.separator();
@@ -845,6 +1304,42 @@ impl eframe::App for StsApp {
                         });
                 });
 
+            // 统计窗口（只读）
+            let doc = &mut self.documents[doc_idx];
+            if doc.show_stats {
+                egui::Window::new(format!("Stats - {}", doc.timesheet.name))
+                    .id(egui::Id::new(format!("doc_stats_{}", doc_id_val)))
+                    .open(&mut doc.show_stats)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        egui::Grid::new("layer_stats_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Layer");
+                                ui.strong("Drawings");
+                                ui.strong("Exposed");
+                                ui.strong("Frames/Drawing");
+                                ui.end_row();
+
+                                for layer in 0..doc.timesheet.layer_count {
+                                    let stats = doc.timesheet.layer_stats(layer);
+                                    ui.label(&doc.timesheet.layer_names[layer]);
+                                    ui.label(stats.unique_drawings.to_string());
+                                    ui.label(stats.total_exposed_frames.to_string());
+                                    ui.label(format!("{:.2}", stats.avg_hold));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+
+            // 折叠时内容不显示，保留上次的尺寸
+            if let Some(resp) = window_resp {
+                let doc = &mut self.documents[doc_idx];
+                let size = content_size.or(doc.window_layout.map(|(_, size)| size));
+                doc.window_layout = size.map(|size| (resp.response.rect.min, size));
+            }
+
             if !window_open {
                 let doc = &self.documents[doc_idx];
                 if doc.is_modified {
@@ -914,10 +1409,38 @@ impl eframe::App for StsApp {
         }
 
         // 移除已关闭的文档
-        self.documents.retain(|d| d.is_open);
+        self.remove_closed_documents();
     }
 }
 
+/// 多表文件（XDTS/TDTS）导入结果摘要；只有一张表且无跳过时不显示
+fn multi_sheet_report(imported: usize, skipped: &[String]) -> Option<String> {
+    if imported <= 1 && skipped.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Imported {} of {} timesheets{}",
+        imported,
+        imported + skipped.len(),
+        skipped_suffix(skipped)
+    ))
+}
+
+/// "; N skipped: a (reason), b (reason)"，没有跳过时为空
+fn skipped_suffix(skipped: &[String]) -> String {
+    if skipped.is_empty() {
+        String::new()
+    } else {
+        format!("; {} skipped: {}", skipped.len(), skipped.join(", "))
+    }
+}
+
+/// 冻结列：矩形左边不超出可见区域左侧
+fn frozen_rect(rect: egui::Rect, clip_rect: egui::Rect) -> egui::Rect {
+    let dx = (clip_rect.left() - rect.left()).max(0.0);
+    rect.translate(egui::vec2(dx, 0.0))
+}
+
 impl StsApp {
     fn on_close_event(&mut self) -> bool {
         // 检查是否有未保存的文档
@@ -927,36 +1450,64 @@ impl StsApp {
             self.show_exit_dialog = true;
             false // 阻止关闭
         } else {
+            // 退出前保存仍打开的窗口布局，以及尚未保存的缩放比例
+            let documents = std::mem::take(&mut self.documents);
+            let layouts_changed = self.remember_window_layouts(&documents);
+            if layouts_changed || self.zoom_changed_at.take().is_some() {
+                let _ = self.settings.save_to_registry();
+            }
+            self.documents = documents;
             true // 允许关闭
         }
     }
 
+    /// 缩放停止 ZOOM_SAVE_DELAY 后保存一次设置，避免每次滚轮都写入
+    fn save_zoom_when_settled(&mut self, ctx: &egui::Context) {
+        let Some(changed_at) = self.zoom_changed_at else {
+            return;
+        };
+        let elapsed = changed_at.elapsed();
+        if elapsed < ZOOM_SAVE_DELAY {
+            ctx.request_repaint_after(ZOOM_SAVE_DELAY - elapsed);
+            return;
+        }
+        self.zoom_changed_at = None;
+        if let Err(e) = self.settings.save_to_registry() {
+            self.error_message = Some(format!("Failed to save settings: {}", e));
+        }
+    }
+
     fn render_document_content(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, doc_idx: usize) {
         let auto_save_enabled = self.settings.auto_save_enabled;
         let colors = CellColors::from_visuals(ui.visuals());
         let doc = &mut self.documents[doc_idx];
 
-        let row_height = 16.0;
-        let col_width = 36.0;
-        let page_col_width = 36.0;
+        let zoom = self.settings.grid_zoom();
+        let row_height = BASE_ROW_HEIGHT * zoom;
+        let col_width = BASE_COL_WIDTH * zoom;
+        let page_col_width = BASE_COL_WIDTH * zoom;
+        let font_size = font_size_for_row(row_height);
         let layer_count = doc.timesheet.layer_count;
 
         // 用于延迟执行的列操作
         let mut pending_insert: Option<usize> = None;
         let mut pending_delete: Option<usize> = None;
+        let mut pending_move: Option<(usize, usize)> = None;
+        let mut pending_renumber: Option<usize> = None;
+        let mut pending_select: Option<usize> = None;
+        let mut rename_warning: Option<String> = None;
 
         // 表头
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing = egui::vec2(0.0, 0.0);
             let (_corner_id, corner_rect) = ui.allocate_space(egui::vec2(page_col_width, row_height));
-            ui.painter().rect_stroke(
-                corner_rect,
-                0.0,
-                egui::Stroke::new(0.0, colors.border_normal),
-            );
+
+            let mut header_rects = Vec::with_capacity(layer_count);
+            let mut drag_released_from: Option<usize> = None;
 
             for i in 0..layer_count {
                 let (id, rect) = ui.allocate_space(egui::vec2(col_width, row_height));
+                header_rects.push(rect);
                 let is_editing = doc.edit_state.editing_layer_name == Some(i);
 
                 let bg_color = if is_editing {
@@ -978,22 +1529,32 @@ impl StsApp {
                     resp.request_focus();
 
                     if resp.lost_focus() || ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        doc.timesheet.layer_names[i] = doc.edit_state.editing_layer_text.clone();
-                        doc.is_modified = true;
-                        doc.edit_state.editing_layer_name = None;
+                        rename_warning = doc.finish_layer_name_edit();
                     }
 
                     if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
                         doc.edit_state.editing_layer_name = None;
                     }
                 } else {
-                    let resp = ui.interact(rect, id, egui::Sense::click());
+                    let resp = ui.interact(rect, id, egui::Sense::click_and_drag());
+
+                    // 拖拽列标题调整顺序
+                    if resp.drag_started_by(egui::PointerButton::Primary) {
+                        doc.selection_state.dragging_layer = Some(i);
+                    }
+                    if resp.drag_stopped() {
+                        drag_released_from = doc.selection_state.dragging_layer.take();
+                    }
+                    if doc.selection_state.dragging_layer.is_some() && resp.contains_pointer() {
+                        ui.painter().rect_stroke(rect, 0.0, egui::Stroke::new(2.0, colors.border_selection));
+                    }
+
                     let layer_name = &doc.timesheet.layer_names[i];
                     ui.painter().text(
                         rect.center(),
                         egui::Align2::CENTER_CENTER,
                         layer_name,
-                        egui::FontId::proportional(11.0),
+                        egui::FontId::proportional(font_size),
                         colors.header_text,
                     );
 
@@ -1013,6 +1574,15 @@ impl StsApp {
                             ui.close_menu();
                         }
                         ui.separator();
+                        if ui.button("Select Column").clicked() {
+                            pending_select = Some(i);
+                            ui.close_menu();
+                        }
+                        if ui.button("Renumber Sequentially").clicked() {
+                            pending_renumber = Some(i);
+                            ui.close_menu();
+                        }
+                        ui.separator();
                         let can_delete = doc.timesheet.layer_count > 1;
                         if ui.add_enabled(can_delete, egui::Button::new("Delete Column")).clicked() {
                             pending_delete = Some(i);
@@ -1021,8 +1591,28 @@ impl StsApp {
                     });
                 }
             }
+
+            // 左上角固定在可见区域左侧，遮住横向滚动到其下方的列标题
+            let corner_rect = frozen_rect(corner_rect, ui.clip_rect());
+            ui.painter().rect_filled(corner_rect, 0.0, ui.visuals().window_fill());
+
+            // 松开时根据指针位置确定目标列
+            if let Some(from) = drag_released_from {
+                if let Some(pos) = ui.input(|i| i.pointer.latest_pos()) {
+                    let to = header_rects.iter()
+                        .position(|r| pos.x < r.right())
+                        .unwrap_or(layer_count.saturating_sub(1));
+                    if to != from {
+                        pending_move = Some((from, to));
+                    }
+                }
+            }
         });
 
+        if rename_warning.is_some() {
+            self.error_message = rename_warning;
+        }
+
         // 执行延迟的列操作（在渲染循环外执行）
         let doc = &mut self.documents[doc_idx];
         if let Some(index) = pending_insert {
@@ -1041,6 +1631,24 @@ impl StsApp {
             // 列操作后立即返回，让下一帧重新渲染
             return;
         }
+        if let Some(layer) = pending_select {
+            doc.select_layer(layer);
+        }
+        if let Some(layer) = pending_renumber {
+            if let Err(e) = doc.renumber_layer(layer, 1) {
+                self.error_message = Some(e.to_string());
+            } else if auto_save_enabled {
+                doc.auto_save();
+            }
+        }
+        if let Some((from, to)) = pending_move {
+            doc.move_layer(from, to);
+            if auto_save_enabled {
+                doc.auto_save();
+            }
+            // 列操作后立即返回，让下一帧重新渲染
+            return;
+        }
 
         ui.separator();
 
@@ -1065,6 +1673,10 @@ impl StsApp {
         let can_start_drag = self.dragging_doc_id.is_none() || self.dragging_doc_id == Some(doc_id);
         let mut any_started_drag = false;
 
+        // 用于延迟执行的帧操作
+        let mut pending_insert_frame: Option<usize> = None;
+        let mut pending_delete_frame: Option<usize> = None;
+
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
             .show_rows(ui, row_height, total_frames, |ui, row_range| {
@@ -1080,19 +1692,73 @@ impl StsApp {
                         let page_str = page_buf_local.format(page);
                         let frame_str = frame_buf_local.format(frame_in_page);
 
-                        let (_page_id, page_rect) = ui.allocate_space(egui::vec2(page_col_width, row_height));
-                        ui.painter().rect_stroke(
+                        let (page_id, page_rect) = ui.allocate_space(egui::vec2(page_col_width, row_height));
+
+                        // 单元格渲染
+                        for layer_idx in 0..layer_count {
+                            if render_cell(ui, doc, layer_idx, frame_idx, col_width, row_height, pointer_pos, pointer_down, &colors, can_start_drag) {
+                                any_started_drag = true;
+                            }
+                        }
+
+                        // 帧号列固定在可见区域左侧（横向滚动时不移出视野），
+                        // 在单元格之后绘制和交互，使其位于被滚动到下方的单元格之上
+                        let page_rect = frozen_rect(page_rect, ui.clip_rect());
+                        ui.painter().rect_filled(page_rect, 0.0, ui.visuals().window_fill());
+
+                        // 帧号列右键菜单
+                        ui.interact(page_rect, page_id, egui::Sense::click()).context_menu(|ui| {
+                            if ui.button("Insert Frame Above").clicked() {
+                                pending_insert_frame = Some(frame_idx);
+                                ui.close_menu();
+                            }
+                            if ui.button("Insert Frame Below").clicked() {
+                                pending_insert_frame = Some(frame_idx + 1);
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                            if ui.button("Delete Frame").clicked() {
+                                pending_delete_frame = Some(frame_idx);
+                                ui.close_menu();
+                            }
+                        });
+
+                        ui.painter().rect_stroke(
                             page_rect,
                             0.0,
                             egui::Stroke::new(1.0, colors.border_normal),
                         );
 
+                        // 页首加粗分隔线
+                        if frame_in_page == 1 && frame_idx > 0 {
+                            ui.painter().hline(
+                                page_rect.x_range(),
+                                page_rect.top(),
+                                egui::Stroke::new(2.0, colors.frame_col_text),
+                            );
+                        }
+
+                        // 每秒的第一帧：刻度线，并在左侧用秒数代替页号
+                        let (second, rem) = doc.timesheet.frame_to_timecode(frame_idx);
+                        let second_buf;
+                        let (left_str, left_color) = if frame_idx > 0 && rem == 0 {
+                            ui.painter().hline(
+                                page_rect.x_range(),
+                                page_rect.top() + 0.5,
+                                egui::Stroke::new(1.0, colors.second_tick),
+                            );
+                            second_buf = format!("{}s", second);
+                            (second_buf.as_str(), colors.second_tick)
+                        } else {
+                            (page_str, colors.frame_col_text)
+                        };
+
                         ui.painter().text(
                             page_rect.left_center() + egui::vec2(3.0, 0.0),
                             egui::Align2::LEFT_CENTER,
-                            page_str,
-                            egui::FontId::monospace(11.0),
-                            colors.frame_col_text,
+                            left_str,
+                            egui::FontId::monospace(font_size),
+                            left_color,
                         );
 
                         if !frame_str.is_empty() {
@@ -1100,17 +1766,10 @@ impl StsApp {
                                 page_rect.right_center() - egui::vec2(3.0, 0.0),
                                 egui::Align2::RIGHT_CENTER,
                                 frame_str,
-                                egui::FontId::monospace(11.0),
+                                egui::FontId::monospace(font_size),
                                 colors.frame_col_text,
                             );
                         }
-
-                        // 单元格渲染
-                        for layer_idx in 0..layer_count {
-                            if render_cell(ui, doc, layer_idx, frame_idx, col_width, row_height, pointer_pos, pointer_down, &colors, can_start_drag) {
-                                any_started_drag = true;
-                            }
-                        }
                     });
                 }
             });
@@ -1120,6 +1779,21 @@ impl StsApp {
             self.dragging_doc_id = Some(doc_id);
         }
 
+        // 执行延迟的帧操作
+        if pending_insert_frame.is_some() || pending_delete_frame.is_some() {
+            let doc = &mut self.documents[doc_idx];
+            let changed = if let Some(at) = pending_insert_frame {
+                doc.insert_frame(at)
+            } else if let Some(at) = pending_delete_frame {
+                doc.delete_frame(at)
+            } else {
+                false
+            };
+            if changed && auto_save_enabled {
+                doc.auto_save();
+            }
+        }
+
         // 鼠标释放
         let doc = &mut self.documents[doc_idx];
         let was_dragging = doc.selection_state.is_dragging;
@@ -1134,6 +1808,7 @@ impl StsApp {
         }
 
         // 右键菜单
+        let mut pending_export_selection: Option<(usize, usize, usize, usize)> = None;
         if let Some(_menu_pos) = doc.context_menu.pos {
             // 检查是否有选择范围
             let has_selection = doc.context_menu.selection.is_some();
@@ -1152,29 +1827,44 @@ impl StsApp {
                         ui.set_min_width(120.0);
 
                         let copy = ui.button("Copy (Ctrl+C)").clicked();
+                        let copy_image = ui.button("Copy as Image").clicked();
                         let cut = ui.button("Cut (Ctrl+X)").clicked();
                         let paste = ui.button("Paste (Ctrl+V)").clicked();
+                        let (paste_hold, paste_transposed) = ui.menu_button("Paste Special", |ui| {
+                            let hold = ui.button("Paste as Hold").clicked();
+                            let transposed = ui.button("Paste Transposed").clicked();
+                            if hold || transposed {
+                                ui.close_menu();
+                            }
+                            (hold, transposed)
+                        }).inner.unwrap_or_default();
 
                         ui.separator();
 
                         let undo = ui.button("Undo (Ctrl+Z)").clicked();
+                        let redo = ui.button("Redo (Ctrl+Y)").clicked();
 
                         ui.separator();
 
                         // Repeat 和 Reverse 只在有选择时可用
                         let repeat = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Repeat...")).clicked();
                         let reverse = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Reverse")).clicked();
+                        let to_holds = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Numbers to Holds")).clicked();
+                        let to_numbers = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Holds to Numbers")).clicked();
+                        let insert_rows = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Insert Rows")).clicked();
+                        let delete_rows = ui.add_enabled(has_selection && is_single_column, egui::Button::new("Delete Rows")).clicked();
                         let sequence_fill = ui.button("Sequence Fill...").clicked();
 
                         ui.separator();
 
                         let copy_ae = ui.button("Copy AE Keyframes").clicked();
+                        let export_selection = ui.add_enabled(has_selection, egui::Button::new("Export Selection as CSV...")).clicked();
 
-                        (copy, cut, paste, undo, repeat, reverse, sequence_fill, copy_ae)
+                        (copy, copy_image, cut, paste, paste_hold, paste_transposed, undo, redo, repeat, reverse, to_holds, to_numbers, insert_rows, delete_rows, sequence_fill, copy_ae, export_selection)
                     }).inner
                 });
 
-            let (copy_clicked, cut_clicked, paste_clicked, undo_clicked, repeat_clicked, reverse_clicked, sequence_fill_clicked, copy_ae_clicked) = menu_result.inner;
+            let (copy_clicked, copy_image_clicked, cut_clicked, paste_clicked, paste_hold_clicked, paste_transposed_clicked, undo_clicked, redo_clicked, repeat_clicked, reverse_clicked, to_holds_clicked, to_numbers_clicked, insert_rows_clicked, delete_rows_clicked, sequence_fill_clicked, copy_ae_clicked, export_selection_clicked) = menu_result.inner;
             let menu_response = menu_result.response;
 
             let doc = &mut self.documents[doc_idx];
@@ -1187,14 +1877,35 @@ impl StsApp {
                 } else if let Some((layer, frame)) = doc.context_menu.pos {
                     let cell = doc.timesheet.get_cell(layer, frame).copied();
                     doc.clipboard = Some(Rc::new(vec![vec![cell]]));
-                    let text = match cell {
-                        Some(CellValue::Number(n)) => n.to_string(),
-                        Some(CellValue::Same) => "-".to_string(),
-                        None => "".to_string(),
-                    };
+                    let text = cell.map(|value| value.to_string()).unwrap_or_default();
                     ctx.output_mut(|o| o.copied_text = text);
                 }
                 doc.context_menu.pos = None;
+            } else if copy_image_clicked {
+                let range = match (doc.context_menu.selection, doc.context_menu.pos) {
+                    (Some(((l1, f1), (l2, f2))), _) => Some((l1.min(l2), f1.min(f2), l1.max(l2), f1.max(f2))),
+                    (None, Some((layer, frame))) => Some((layer, frame, layer, frame)),
+                    _ => None,
+                };
+                if let Some((min_layer, min_frame, max_layer, max_frame)) = range {
+                    let range = (min_layer, min_frame, max_layer, max_frame);
+                    let image = ctx.fonts(|f| {
+                        crate::ui::snapshot::render_range_image(&doc.timesheet, range, &colors, f.lock().fonts.definitions())
+                    });
+                    match image.and_then(|image| crate::ui::snapshot::copy_image_to_clipboard(&image)) {
+                        Ok(()) => {
+                            self.error_message = Some("Selection copied as image".to_string());
+                        }
+                        Err(e) => {
+                            // 图片剪贴板不可用时退回文本复制
+                            doc.selection_state.selection_start = Some((min_layer, min_frame));
+                            doc.selection_state.selection_end = Some((max_layer, max_frame));
+                            doc.copy_selection(ctx);
+                            self.error_message = Some(format!("Copy as image failed ({}), copied as text instead", e));
+                        }
+                    }
+                }
+                doc.context_menu.pos = None;
             } else if cut_clicked {
                 if let Some((start, end)) = doc.context_menu.selection {
                     doc.selection_state.selection_start = Some(start);
@@ -1216,13 +1927,46 @@ impl StsApp {
                 if let Some((layer, frame)) = doc.context_menu.pos {
                     doc.selection_state.selected_cell = Some((layer, frame));
                 }
-                doc.paste_clipboard();
+                let truncated = doc.paste_clipboard();
+                if truncated > 0 {
+                    self.error_message = Some(format!("Paste truncated: {} column(s) past the last layer", truncated));
+                }
+                if auto_save_enabled { doc.auto_save(); }
+                doc.context_menu.pos = None;
+            } else if paste_hold_clicked {
+                if let Some((start, end)) = doc.context_menu.selection {
+                    doc.selection_state.selection_start = Some(start);
+                    doc.selection_state.selection_end = Some(end);
+                } else if let Some((layer, frame)) = doc.context_menu.pos {
+                    doc.selection_state.selected_cell = Some((layer, frame));
+                    doc.selection_state.selection_start = None;
+                    doc.selection_state.selection_end = None;
+                }
+                match doc.paste_as_hold() {
+                    Ok(_) => {
+                        if auto_save_enabled { doc.auto_save(); }
+                    }
+                    Err(e) => self.error_message = Some(e.to_string()),
+                }
+                doc.context_menu.pos = None;
+            } else if paste_transposed_clicked {
+                if let Some((layer, frame)) = doc.context_menu.pos {
+                    doc.selection_state.selected_cell = Some((layer, frame));
+                }
+                let truncated = doc.paste_transposed();
+                if truncated > 0 {
+                    self.error_message = Some(format!("Paste truncated: {} column(s) past the last layer", truncated));
+                }
                 if auto_save_enabled { doc.auto_save(); }
                 doc.context_menu.pos = None;
             } else if undo_clicked {
                 doc.undo();
                 if auto_save_enabled { doc.auto_save(); }
                 doc.context_menu.pos = None;
+            } else if redo_clicked {
+                doc.redo();
+                if auto_save_enabled { doc.auto_save(); }
+                doc.context_menu.pos = None;
             } else if repeat_clicked {
                 // 打开 Repeat 弹窗
                 if let Some(((start_layer, start_frame), (end_layer, end_frame))) = doc.context_menu.selection {
@@ -1248,6 +1992,41 @@ impl StsApp {
                     }
                 }
                 doc.context_menu.pos = None;
+            } else if to_holds_clicked || to_numbers_clicked {
+                if let Some((start, end)) = doc.context_menu.selection {
+                    doc.selection_state.selection_start = Some(start);
+                    doc.selection_state.selection_end = Some(end);
+                    let result = if to_holds_clicked { doc.numbers_to_holds() } else { doc.holds_to_numbers() };
+                    match result {
+                        Ok(count) => {
+                            if count > 0 && auto_save_enabled {
+                                doc.auto_save();
+                            }
+                            self.error_message = Some(format!("Converted {} cell(s)", count));
+                        }
+                        Err(e) => self.error_message = Some(e.to_string()),
+                    }
+                }
+                doc.context_menu.pos = None;
+            } else if insert_rows_clicked || delete_rows_clicked {
+                // 在选区所在列插入/删除与选区等长的帧，仅移动该列
+                if let Some(((layer, start_frame), (_, end_frame))) = doc.context_menu.selection {
+                    let at = start_frame.min(end_frame);
+                    let count = start_frame.abs_diff(end_frame) + 1;
+                    let changed = if insert_rows_clicked {
+                        doc.insert_rows(layer, at, count)
+                    } else {
+                        doc.delete_rows(layer, at, count)
+                    };
+                    if !changed {
+                        if insert_rows_clicked {
+                            self.error_message = Some(format!("Cannot insert rows: sheet would exceed {} frames", sts_rust::limits::max_frames()));
+                        }
+                    } else if auto_save_enabled {
+                        doc.auto_save();
+                    }
+                }
+                doc.context_menu.pos = None;
             } else if sequence_fill_clicked {
                 // 打开 Sequence Fill 弹窗
                 if let Some((layer, frame)) = doc.context_menu.pos {
@@ -1257,9 +2036,18 @@ impl StsApp {
                 }
                 doc.context_menu.pos = None;
             } else if copy_ae_clicked {
-                // Copy AE Keyframes - use clicked cell's layer
-                if let Some((layer, _frame)) = doc.context_menu.pos {
-                    let ae_version = self.settings.ae_keyframe_version.as_str();
+                let ae_version = self.settings.ae_keyframe_version.as_str();
+                if let Some(((start_layer, _), (end_layer, _))) = doc.context_menu.selection.filter(|((s, _), (e, _))| s != e) {
+                    // 多列选择 - 每列一个数据块
+                    let min_layer = start_layer.min(end_layer);
+                    let max_layer = start_layer.max(end_layer);
+                    if let Err(e) = doc.copy_ae_keyframes_range(ctx, min_layer, max_layer, ae_version) {
+                        self.error_message = Some(e.to_string());
+                    } else {
+                        self.error_message = Some(format!("AE Time Remap keyframes copied ({} layers)", max_layer - min_layer + 1));
+                    }
+                } else if let Some((layer, _frame)) = doc.context_menu.pos {
+                    // Copy AE Keyframes - use clicked cell's layer
                     if let Err(e) = doc.copy_ae_keyframes(ctx, layer, ae_version) {
                         self.error_message = Some(e.to_string());
                     } else {
@@ -1267,10 +2055,16 @@ impl StsApp {
                     }
                 }
                 doc.context_menu.pos = None;
+            } else if export_selection_clicked {
+                // 文件对话框需要 &mut self，菜单关闭后再导出
+                if let Some(((l1, f1), (l2, f2))) = doc.context_menu.selection {
+                    pending_export_selection = Some((l1.min(l2), f1.min(f2), l1.max(l2), f1.max(f2)));
+                }
+                doc.context_menu.pos = None;
             }
 
             // 点击菜单外部关闭
-            if !copy_clicked && !cut_clicked && !paste_clicked && !undo_clicked && !repeat_clicked && !reverse_clicked && !sequence_fill_clicked && !copy_ae_clicked {
+            if !copy_clicked && !cut_clicked && !paste_clicked && !undo_clicked && !redo_clicked && !repeat_clicked && !reverse_clicked && !sequence_fill_clicked && !copy_ae_clicked {
                 let clicked_outside = ctx.input(|i| {
                     if i.pointer.primary_clicked() {
                         if let Some(pos) = i.pointer.interact_pos() {
@@ -1294,6 +2088,10 @@ impl StsApp {
             }
         }
 
+        if let Some(range) = pending_export_selection {
+            self.export_range_to_csv(doc_id, Some(range));
+        }
+
         // Repeat 弹窗
         let doc = &mut self.documents[doc_idx];
         if doc.repeat_dialog.open {
@@ -1424,6 +2222,265 @@ impl StsApp {
             }
         }
 
+        // Replace Values 弹窗
+        let doc = &mut self.documents[doc_idx];
+        if doc.replace_dialog.open {
+            let mut should_execute = false;
+            let mut should_cancel = false;
+            let current_layer = doc.selection_state.selected_cell.map_or(0, |(layer, _)| layer);
+            let has_selection = doc.get_selection_range().is_some();
+
+            egui::Window::new("Replace Values")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut doc.replace_dialog.open)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Find:");
+                        ui.add(egui::DragValue::new(&mut doc.replace_dialog.from).range(0..=9999));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Replace with:");
+                        ui.add(egui::DragValue::new(&mut doc.replace_dialog.to).range(0..=9999));
+                    });
+
+                    ui.separator();
+
+                    let scope = &mut doc.replace_dialog.scope;
+                    ui.add_enabled_ui(has_selection, |ui| {
+                        ui.radio_value(scope, ReplaceScope::Selection, "Selection");
+                    });
+                    let layer_name = doc.timesheet.layer_names.get(current_layer).cloned().unwrap_or_default();
+                    ui.radio_value(scope, ReplaceScope::Layer(current_layer), format!("Layer {}", layer_name));
+                    ui.radio_value(scope, ReplaceScope::Sheet, "Whole sheet");
+
+                    ui.separator();
+
+                    let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    ui.horizontal(|ui| {
+                        if ui.button("OK").clicked() || enter_pressed {
+                            should_execute = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_cancel = true;
+                        }
+                    });
+                });
+
+            if should_cancel {
+                doc.replace_dialog.open = false;
+            }
+
+            if should_execute {
+                let from = doc.replace_dialog.from;
+                let to = doc.replace_dialog.to;
+                let count = doc.replace_value(doc.replace_dialog.scope, from, to);
+                if count > 0 && auto_save_enabled {
+                    doc.auto_save();
+                }
+                self.error_message = Some(format!("Replaced {} cell(s): {} -> {}", count, from, to));
+                doc.replace_dialog.open = false;
+            }
+        }
+
+        // Offset Numbers 弹窗
+        let doc = &mut self.documents[doc_idx];
+        if doc.offset_dialog.open {
+            let mut should_execute = false;
+            let mut should_cancel = false;
+            let current_layer = doc.selection_state.selected_cell.map_or(0, |(layer, _)| layer);
+            let has_selection = doc.get_selection_range().is_some();
+
+            egui::Window::new("Offset Numbers")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut doc.offset_dialog.open)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Add:");
+                        ui.add(egui::DragValue::new(&mut doc.offset_dialog.delta).range(-9999..=9999));
+                    });
+                    ui.label("Numbers below 1 become blank; holds are kept.");
+
+                    ui.separator();
+
+                    let scope = &mut doc.offset_dialog.scope;
+                    ui.add_enabled_ui(has_selection, |ui| {
+                        ui.radio_value(scope, ReplaceScope::Selection, "Selection");
+                    });
+                    let layer_name = doc.timesheet.layer_names.get(current_layer).cloned().unwrap_or_default();
+                    ui.radio_value(scope, ReplaceScope::Layer(current_layer), format!("Layer {}", layer_name));
+                    ui.radio_value(scope, ReplaceScope::Sheet, "Whole sheet");
+
+                    ui.separator();
+
+                    let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    ui.horizontal(|ui| {
+                        if ui.button("OK").clicked() || enter_pressed {
+                            should_execute = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_cancel = true;
+                        }
+                    });
+                });
+
+            if should_cancel {
+                doc.offset_dialog.open = false;
+            }
+
+            if should_execute {
+                let delta = doc.offset_dialog.delta;
+                match doc.offset_values(doc.offset_dialog.scope, delta) {
+                    Ok(count) => {
+                        if count > 0 && auto_save_enabled {
+                            doc.auto_save();
+                        }
+                        self.error_message = Some(format!("Offset {} cell(s) by {:+}", count, delta));
+                    }
+                    Err(e) => self.error_message = Some(e.to_string()),
+                }
+                doc.offset_dialog.open = false;
+            }
+        }
+
+        // Set Duration 弹窗
+        let doc = &mut self.documents[doc_idx];
+        if doc.duration_dialog.open {
+            let mut should_execute = false;
+            let mut should_cancel = false;
+            let max_rem = doc.timesheet.framerate.max(1) - 1;
+            let current_frames = doc.timesheet.total_frames();
+            let timesheet = &doc.timesheet;
+            let dialog = &mut doc.duration_dialog;
+
+            egui::Window::new("Set Duration")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut dialog.open)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Duration:");
+                        ui.add(egui::DragValue::new(&mut dialog.seconds).range(0..=3600).suffix("s"));
+                        ui.label("+");
+                        ui.add(egui::DragValue::new(&mut dialog.frames).range(0..=max_rem).suffix("k"));
+                    });
+
+                    let target = timesheet.timecode_to_frame(dialog.seconds, dialog.frames);
+                    ui.label(format!("Total: {} frames (currently {})", target, current_frames));
+
+                    // 缩短时提示会丢弃的内容
+                    if target < current_frames {
+                        let discarded = timesheet.occupied_from(target);
+                        if discarded > 0 {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("⚠ Discards {} non-empty cell(s) after frame {}", discarded, target),
+                            );
+                        }
+                    }
+                    if target > sts_rust::limits::max_frames() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ Exceeds the limit of {} frames", sts_rust::limits::max_frames()),
+                        );
+                    } else if target > sts_rust::STS_MAX_FRAMES {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ Exceeds STS limit of {} frames; use Export CSV to keep every frame", sts_rust::STS_MAX_FRAMES),
+                        );
+                    }
+
+                    ui.separator();
+
+                    let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    ui.horizontal(|ui| {
+                        if ui.button("OK").clicked() || enter_pressed {
+                            should_execute = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_cancel = true;
+                        }
+                    });
+                });
+
+            if should_cancel {
+                doc.duration_dialog.open = false;
+            }
+
+            if should_execute {
+                let target = doc.timesheet.timecode_to_frame(doc.duration_dialog.seconds, doc.duration_dialog.frames);
+                match doc.set_duration(target) {
+                    Ok(discarded) => {
+                        if target != current_frames && auto_save_enabled {
+                            doc.auto_save();
+                        }
+                        if discarded > 0 {
+                            self.warning_message = Some(format!(
+                                "Duration set to {} frames; discarded {} non-empty cell(s)",
+                                target, discarded
+                            ));
+                        } else {
+                            self.error_message = Some(format!("Duration set to {} frames", target));
+                        }
+                    }
+                    Err(e) => self.error_message = Some(e.to_string()),
+                }
+                doc.duration_dialog.open = false;
+            }
+        }
+
+        // Clear All 确认弹窗
+        let doc = &mut self.documents[doc_idx];
+        if doc.clear_dialog_open {
+            let mut should_clear = false;
+            let mut should_cancel = false;
+
+            egui::Window::new("Clear All")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut doc.clear_dialog_open)
+                .show(ctx, |ui| {
+                    ui.label("Clear all cells in this sheet?");
+                    ui.label("This action can be undone.");
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Clear").clicked() {
+                            should_clear = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_cancel = true;
+                        }
+                    });
+                });
+
+            if should_cancel {
+                doc.clear_dialog_open = false;
+            }
+
+            if should_clear {
+                doc.clear_all_cells();
+                if auto_save_enabled {
+                    doc.auto_save();
+                }
+                doc.clear_dialog_open = false;
+            }
+        }
+
+        // Ctrl+滚轮 缩放表格
+        if ui.ui_contains_pointer() {
+            let zoom_delta = ctx.input(|i| i.zoom_delta());
+            if zoom_delta != 1.0 {
+                let percent = (self.settings.grid_zoom_percent as f32 * zoom_delta).round() as u32;
+                let percent = percent.clamp(MIN_GRID_ZOOM_PERCENT, MAX_GRID_ZOOM_PERCENT);
+                if percent != self.settings.grid_zoom_percent {
+                    self.settings.grid_zoom_percent = percent;
+                    self.zoom_changed_at = Some(std::time::Instant::now());
+                }
+            }
+        }
+
         // 检测鼠标交互，更新活跃文档
         let doc = &self.documents[doc_idx];
         if ui.ui_contains_pointer() || doc.edit_state.editing_cell.is_some() {
@@ -1442,7 +2499,9 @@ impl StsApp {
         let doc = &mut self.documents[doc_idx];
 
         // 如果有对话框打开，不处理键盘事件
-        if doc.repeat_dialog.open || doc.sequence_fill_dialog.open {
+        if doc.repeat_dialog.open || doc.sequence_fill_dialog.open || doc.replace_dialog.open
+            || doc.offset_dialog.open || doc.duration_dialog.open || doc.clear_dialog_open || doc.goto_focused
+        {
             return;
         }
 
@@ -1453,11 +2512,18 @@ impl StsApp {
         let mut should_paste = false;
         let mut paste_text: Option<String> = None;
         let mut should_undo = false;
+        let mut should_redo = false;
         let mut should_delete = false;
         let mut should_save = false;
+        let mut should_save_as = false;
+        let mut should_export = false;
+        let mut should_fill_down = false;
+        let mut should_select_all = false;
+        let mut should_select_layer = false;
 
         let is_editing = doc.edit_state.editing_cell.is_some() || doc.edit_state.editing_layer_name.is_some();
         let mut jump_step_delta: i32 = 0;
+        let mut shift_delta: i32 = 0;
 
         ctx.input(|i| {
             for event in &i.events {
@@ -1484,19 +2550,66 @@ impl StsApp {
                 should_undo = true;
             }
 
+            // Ctrl+Shift+Z / Ctrl+Y 重做
+            if i.modifiers.command
+                && ((i.key_pressed(egui::Key::Z) && i.modifiers.shift) || i.key_pressed(egui::Key::Y))
+            {
+                should_redo = true;
+            }
+
+            // Ctrl+S 保存，Ctrl+Shift+S 另存为
             if i.modifiers.command && i.key_pressed(egui::Key::S) {
-                should_save = true;
+                if i.modifiers.shift {
+                    should_save_as = true;
+                } else {
+                    should_save = true;
+                }
+            }
+
+            if i.modifiers.command && i.key_pressed(egui::Key::E) {
+                should_export = true;
+            }
+
+            // Ctrl+D 向下保持到最后一帧
+            if i.modifiers.command && i.key_pressed(egui::Key::D) {
+                should_fill_down = true;
+            }
+
+            // Ctrl+A 全选，Ctrl+Shift+A 选中当前列
+            if i.modifiers.command && i.key_pressed(egui::Key::A) {
+                if i.modifiers.shift {
+                    should_select_layer = true;
+                } else {
+                    should_select_all = true;
+                }
             }
 
             if i.key_pressed(egui::Key::Delete) {
                 should_delete = true;
             }
+
+            // Alt+↑/↓ 整列提前/延后一帧
+            if i.modifiers.alt {
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    shift_delta = -1;
+                } else if i.key_pressed(egui::Key::ArrowDown) {
+                    shift_delta = 1;
+                }
+            }
         });
 
         if should_save {
             self.save_document(doc_id);
             return;
         }
+        if should_save_as {
+            self.save_document_as(doc_id);
+            return;
+        }
+        if should_export {
+            self.export_to_csv(doc_id);
+            return;
+        }
 
         // Update jump step (only when not editing)
         if jump_step_delta != 0 {
@@ -1509,6 +2622,46 @@ impl StsApp {
             if auto_save_enabled { doc.auto_save(); }
         }
 
+        if should_redo {
+            doc.redo();
+            if auto_save_enabled { doc.auto_save(); }
+        }
+
+        // 仅在选中整列时平移该列，否则按普通方向键处理
+        if !is_editing && shift_delta != 0 {
+            let total_frames = doc.timesheet.total_frames();
+            if let Some((min_layer, 0, max_layer, max_frame)) = doc.get_selection_range() {
+                if min_layer == max_layer && max_frame + 1 == total_frames {
+                    if doc.shift_layer(min_layer, shift_delta) && auto_save_enabled {
+                        doc.auto_save();
+                    }
+                    return;
+                }
+            }
+        }
+
+        if !is_editing && should_fill_down {
+            match doc.fill_down() {
+                Ok(count) => {
+                    if count > 0 && auto_save_enabled {
+                        doc.auto_save();
+                    }
+                    self.error_message = Some(format!("Filled down {} cell(s)", count));
+                }
+                Err(e) => self.error_message = Some(e.to_string()),
+            }
+        }
+
+        if !is_editing && should_select_all {
+            doc.select_all();
+        }
+
+        if !is_editing && should_select_layer {
+            if let Some((layer, _)) = doc.selection_state.selected_cell {
+                doc.select_layer(layer);
+            }
+        }
+
         if !is_editing && should_delete {
             doc.delete_selection();
             if auto_save_enabled { doc.auto_save(); }
@@ -1537,13 +2690,11 @@ impl StsApp {
                 }
             } else if should_paste {
                 // 优先从系统剪贴板文本粘贴，失败则回退到内部剪贴板
-                let pasted = if let Some(ref text) = paste_text {
-                    doc.paste_from_text(text)
-                } else {
-                    false
-                };
-                if !pasted {
-                    doc.paste_clipboard();
+                let truncated = paste_text.as_deref()
+                    .and_then(|text| doc.paste_from_text(text))
+                    .unwrap_or_else(|| doc.paste_clipboard());
+                if truncated > 0 {
+                    self.error_message = Some(format!("Paste truncated: {} column(s) past the last layer", truncated));
                 }
                 if auto_save_enabled { doc.auto_save(); }
             }
@@ -1553,6 +2704,7 @@ impl StsApp {
         if let Some((layer, frame)) = doc.edit_state.editing_cell {
             let has_input = !doc.edit_state.editing_text.is_empty();
             let total_frames = doc.timesheet.total_frames();
+            let step = doc.jump_step.max(1);
             let mut did_edit = false;
 
             ctx.input(|i| {
@@ -1564,10 +2716,11 @@ impl StsApp {
                     doc.edit_state.editing_cell = None;
                     doc.edit_state.editing_text.clear();
                 } else {
+                    // ↑/↓ 按 jump_step 移动（不超出表格范围）
                     let new_pos = if i.key_pressed(egui::Key::ArrowUp) && frame > 0 {
-                        Some((layer, frame - 1))
+                        Some((layer, frame.saturating_sub(step)))
                     } else if i.key_pressed(egui::Key::ArrowDown) && frame + 1 < total_frames {
-                        Some((layer, frame + 1))
+                        Some((layer, (frame + step).min(total_frames - 1)))
                     } else if i.key_pressed(egui::Key::ArrowLeft) && layer > 0 {
                         Some((layer - 1, frame))
                     } else if i.key_pressed(egui::Key::ArrowRight) && layer < layer_count - 1 {
@@ -1596,6 +2749,7 @@ impl StsApp {
             }
         } else if let Some((layer, frame)) = doc.selection_state.selected_cell {
             let total_frames = doc.timesheet.total_frames();
+            let step = doc.jump_step.max(1);
             let mut did_modify = false;
 
             ctx.input(|i| {
@@ -1636,10 +2790,11 @@ impl StsApp {
                     doc.selection_state.selected_cell = Some((layer + 1, frame));
                     doc.selection_state.auto_scroll_to_selection = true;
                 } else {
+                    // ↑/↓ 按 jump_step 移动（不超出表格范围）
                     let new_pos = if i.key_pressed(egui::Key::ArrowUp) && frame > 0 {
-                        Some((layer, frame - 1))
+                        Some((layer, frame.saturating_sub(step)))
                     } else if i.key_pressed(egui::Key::ArrowDown) && frame + 1 < total_frames {
-                        Some((layer, frame + 1))
+                        Some((layer, (frame + step).min(total_frames - 1)))
                     } else if i.key_pressed(egui::Key::ArrowLeft) && layer > 0 {
                         Some((layer - 1, frame))
                     } else if i.key_pressed(egui::Key::ArrowRight) && layer < layer_count - 1 {
@@ -1676,3 +2831,32 @@ impl StsApp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reopen_keeps_unsaved_changes_modified() {
+        let mut app = StsApp::default();
+        for (path, is_modified) in [("saved.sts", false), ("discarded.sts", true)] {
+            let mut doc = Document::new(app.next_doc_id, TimeSheet::default(), Some(path.to_string()));
+            app.next_doc_id += 1;
+            doc.is_modified = is_modified;
+            doc.is_open = false;
+            app.documents.push(doc);
+        }
+        app.remove_closed_documents();
+        assert!(app.documents.is_empty());
+
+        app.reopen_closed_document();
+        let doc = app.documents.last().unwrap();
+        assert_eq!(doc.file_path.as_deref(), Some("discarded.sts"));
+        assert!(doc.is_modified);
+
+        app.reopen_closed_document();
+        let doc = app.documents.last().unwrap();
+        assert_eq!(doc.file_path.as_deref(), Some("saved.sts"));
+        assert!(!doc.is_modified);
+    }
+}
@@ -0,0 +1,31 @@
+//! Headless timesheet converter
+//!
+//! Usage: sts-convert <input> <output>
+//!
+//! Formats are chosen by file extension; see `sts_rust::convert` for the
+//! supported input/output pairs.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: sts-convert <input> <output>");
+        eprintln!("Input:  .sts .csv .xdts .tdts .json .sxf .txt (AE keyframes)");
+        eprintln!("Output: .sts .csv .xdts .tdts .json .jsx (AE script)");
+        return ExitCode::from(2);
+    }
+
+    match sts_rust::convert(&args[1], &args[2]) {
+        Ok(written) => {
+            for path in written {
+                println!("{}", path);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
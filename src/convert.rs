@@ -0,0 +1,143 @@
+//! Headless format conversion
+//!
+//! Input and output formats are chosen by file extension (case-insensitive):
+//!
+//! | Extension | Input | Output |
+//! |-----------|-------|--------|
+//! | `.sts`    | yes   | yes    |
+//! | `.csv`    | yes   | yes (default options: 动画 header, GB2312, keyframes only) |
+//! | `.xdts`   | yes   | yes    |
+//! | `.tdts`   | yes   | yes    |
+//! | `.json`   | yes   | yes    |
+//! | `.sxf`    | yes   | no     |
+//! | `.txt` (AE keyframe data) | yes | no |
+//! | `.jsx` (AE script)  | no    | yes    |
+//!
+//! XDTS/TDTS files can hold several sheets. When the input yields more than one
+//! sheet, each is written to `<output stem>_<n>.<ext>` (numbered from 1).
+
+use std::path::Path;
+
+use crate::error::{Result, StsError};
+use crate::formats;
+use crate::models::timesheet::TimeSheet;
+
+/// Lowercased extension of a path ("" if none)
+fn extension_of(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Read every timesheet contained in a file, dispatching on its extension
+pub fn read_timesheets(path: &str) -> Result<Vec<TimeSheet>> {
+    let extension = extension_of(path);
+    let timesheets = match extension.as_str() {
+        "sts" => vec![formats::parse_sts_file(path)?],
+        "csv" => vec![formats::parse_csv_file(path)?],
+        "xdts" => formats::parse_xdts_file(path)?,
+        "tdts" => formats::parse_tdts_file(path)?.timesheets,
+        "json" => vec![formats::parse_json_file(path)?],
+        "txt" => vec![formats::parse_ae_keyframe_file(path)?],
+        "sxf" => {
+            let groups = formats::parse_sxf_groups(path)?;
+            let filename = Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("untitled");
+            vec![formats::groups_to_timesheet(&groups, filename)?]
+        }
+        _ => return Err(StsError::InvalidData(format!("Unsupported input format: {}", extension))),
+    };
+
+    if timesheets.is_empty() {
+        return Err(StsError::InvalidData(format!("No timesheets found in {}", path)));
+    }
+    Ok(timesheets)
+}
+
+/// Write one timesheet, choosing the format from the output extension
+pub fn write_timesheet(timesheet: &TimeSheet, path: &str) -> Result<()> {
+    let extension = extension_of(path);
+    match extension.as_str() {
+        "sts" => formats::write_sts_file(timesheet, path),
+        "csv" => formats::write_csv_file(timesheet, path),
+        "xdts" => formats::write_xdts_file(timesheet, path),
+        "tdts" => formats::write_tdts_file(timesheet, &timesheet.name, path),
+        "json" => formats::write_json_file(timesheet, path),
+        "jsx" => formats::write_ae_jsx(timesheet, path),
+        _ => Err(StsError::InvalidData(format!("Unsupported output format: {}", extension))),
+    }
+}
+
+/// Convert `input` to `output`, returning the paths that were written
+pub fn convert(input: &str, output: &str) -> Result<Vec<String>> {
+    let timesheets = read_timesheets(input)?;
+
+    if timesheets.len() == 1 {
+        write_timesheet(&timesheets[0], output)?;
+        return Ok(vec![output.to_string()]);
+    }
+
+    let output_path = Path::new(output);
+    let stem = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let extension = extension_of(output);
+
+    let mut written = Vec::with_capacity(timesheets.len());
+    for (i, timesheet) in timesheets.iter().enumerate() {
+        let path = output_path.with_file_name(format!("{}_{}.{}", stem, i + 1, extension));
+        let path = path.to_string_lossy().into_owned();
+        write_timesheet(timesheet, &path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timesheet::CellValue;
+
+    #[test]
+    fn test_convert_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let sts = dir.path().join("cut.sts");
+        let json = dir.path().join("cut.JSON");
+        let back = dir.path().join("back.sts");
+
+        let mut ts = TimeSheet::new("cut".to_string(), 24, 2, 144);
+        ts.ensure_frames(24);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(1, 12, Some(CellValue::Number(3)));
+        formats::write_sts_file(&ts, sts.to_str().unwrap()).unwrap();
+
+        let written = convert(sts.to_str().unwrap(), json.to_str().unwrap()).unwrap();
+        assert_eq!(written, vec![json.to_str().unwrap().to_string()]);
+        convert(json.to_str().unwrap(), back.to_str().unwrap()).unwrap();
+
+        let reloaded = formats::parse_sts_file(back.to_str().unwrap()).unwrap();
+        assert_eq!(reloaded.total_frames(), 24);
+        assert_eq!(reloaded.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(reloaded.get_cell(1, 12), Some(&CellValue::Number(3)));
+    }
+
+    #[test]
+    fn test_convert_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        let sts = dir.path().join("cut.sts");
+        let mut ts = TimeSheet::new("cut".to_string(), 24, 1, 144);
+        ts.ensure_frames(1);
+        formats::write_sts_file(&ts, sts.to_str().unwrap()).unwrap();
+
+        assert!(matches!(
+            convert(sts.to_str().unwrap(), dir.path().join("out.sxf").to_str().unwrap()),
+            Err(StsError::InvalidData(_))
+        ));
+        assert!(matches!(
+            convert(dir.path().join("in.doc").to_str().unwrap(), sts.to_str().unwrap()),
+            Err(StsError::InvalidData(_))
+        ));
+    }
+}
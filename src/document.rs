@@ -2,9 +2,11 @@
 
 use eframe::egui;
 use std::collections::VecDeque;
+use std::fmt::Write as _;
 use std::rc::Rc;
 use sts_rust::TimeSheet;
-use sts_rust::models::timesheet::CellValue;
+use sts_rust::models::timesheet::{CellValue, LayerCells};
+use sts_rust::limits::max_frames;
 
 // 撤销栈限制
 pub const MAX_UNDO_ACTIONS: usize = 100;
@@ -30,6 +32,14 @@ pub enum UndoAction {
         name: String,
         cells: Vec<Option<CellValue>>,
     },
+    MoveLayer {
+        from: usize,
+        to: usize,
+    },
+    // 整表快照（插入/删除帧等改变帧数的操作）
+    ReplaceCells {
+        cells: Rc<Vec<LayerCells>>,
+    },
 }
 
 // 编辑状态
@@ -63,6 +73,8 @@ pub struct SelectionState {
     pub selection_end: Option<(usize, usize)>,
     pub is_dragging: bool,
     pub auto_scroll_to_selection: bool,
+    // 正在拖拽的列标题（用于调整列顺序）
+    pub dragging_layer: Option<usize>,
 }
 
 impl Default for SelectionState {
@@ -73,6 +85,7 @@ impl Default for SelectionState {
             selection_end: None,
             is_dragging: false,
             auto_scroll_to_selection: false,
+            dragging_layer: None,
         }
     }
 }
@@ -140,6 +153,58 @@ impl Default for SequenceFillDialogState {
     }
 }
 
+// 替换数值的作用范围
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReplaceScope {
+    Selection,
+    Layer(usize),
+    Sheet,
+}
+
+// 替换数值弹窗状态
+pub struct ReplaceDialogState {
+    pub open: bool,
+    pub scope: ReplaceScope,
+    pub from: u32,
+    pub to: u32,
+}
+
+impl Default for ReplaceDialogState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            scope: ReplaceScope::Sheet,
+            from: 1,
+            to: 1,
+        }
+    }
+}
+
+// 编号整体偏移弹窗状态
+pub struct OffsetDialogState {
+    pub open: bool,
+    pub scope: ReplaceScope,
+    pub delta: i32,
+}
+
+impl Default for OffsetDialogState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            scope: ReplaceScope::Sheet,
+            delta: 100,
+        }
+    }
+}
+
+// 修改总时长弹窗状态
+#[derive(Default)]
+pub struct DurationDialogState {
+    pub open: bool,
+    pub seconds: u32,
+    pub frames: u32,
+}
+
 // 剪贴板数据
 pub type ClipboardData = Rc<Vec<Vec<Option<CellValue>>>>;
 
@@ -155,9 +220,19 @@ pub struct Document {
     pub context_menu: ContextMenuState,
     pub clipboard: Option<ClipboardData>,
     pub undo_stack: VecDeque<UndoAction>,
+    pub redo_stack: VecDeque<UndoAction>,
     pub repeat_dialog: RepeatDialogState,
     pub sequence_fill_dialog: SequenceFillDialogState,
+    pub replace_dialog: ReplaceDialogState,
+    pub offset_dialog: OffsetDialogState,
+    pub duration_dialog: DurationDialogState,
+    pub show_stats: bool,  // 统计窗口是否显示
+    pub color_by_number: bool,  // 按编号为单元格着色（仅显示）
+    pub clear_dialog_open: bool,  // Clear All 确认弹窗
+    pub goto_text: String,  // 跳转框输入（"p3" 为页，纯数字为帧）
+    pub goto_focused: bool,  // 跳转框有焦点时不处理表格快捷键
     pub jump_step: usize,  // Enter key jump step (adjustable with / and *)
+    pub window_layout: Option<(egui::Pos2, egui::Vec2)>,  // 窗口位置和内容尺寸，关闭时按文件保存
 }
 
 impl Document {
@@ -173,9 +248,19 @@ impl Document {
             context_menu: ContextMenuState::default(),
             clipboard: None,
             undo_stack: VecDeque::with_capacity(MAX_UNDO_ACTIONS),
+            redo_stack: VecDeque::new(),
             repeat_dialog: RepeatDialogState::default(),
             sequence_fill_dialog: SequenceFillDialogState::default(),
+            replace_dialog: ReplaceDialogState::default(),
+            offset_dialog: OffsetDialogState::default(),
+            duration_dialog: DurationDialogState::default(),
+            show_stats: false,
+            color_by_number: false,
+            clear_dialog_open: false,
+            goto_text: String::new(),
+            goto_focused: false,
             jump_step: 1,
+            window_layout: None,
         }
     }
 
@@ -237,11 +322,20 @@ impl Document {
                 let mut buf = itoa::Buffer::new();
                 self.edit_state.editing_text.push_str(buf.format(*n));
             }
+            Some(value @ CellValue::Labeled(..)) => {
+                let _ = write!(self.edit_state.editing_text, "{}", value);
+            }
             Some(CellValue::Same) => {
                 if frame > 0 {
-                    if let Some(CellValue::Number(n)) = self.timesheet.get_cell(layer, frame - 1) {
-                        let mut buf = itoa::Buffer::new();
-                        self.edit_state.editing_text.push_str(buf.format(*n));
+                    match self.timesheet.get_cell(layer, frame - 1) {
+                        Some(CellValue::Number(n)) => {
+                            let mut buf = itoa::Buffer::new();
+                            self.edit_state.editing_text.push_str(buf.format(*n));
+                        }
+                        Some(value @ CellValue::Labeled(..)) => {
+                            let _ = write!(self.edit_state.editing_text, "{}", value);
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -263,11 +357,20 @@ impl Document {
                 let mut buf = itoa::Buffer::new();
                 self.edit_state.editing_text.push_str(buf.format(*n));
             }
+            Some(value @ CellValue::Labeled(..)) => {
+                let _ = write!(self.edit_state.editing_text, "{}", value);
+            }
             Some(CellValue::Same) => {
                 if frame > 0 {
-                    if let Some(CellValue::Number(n)) = self.timesheet.get_cell(layer, frame - 1) {
-                        let mut buf = itoa::Buffer::new();
-                        self.edit_state.editing_text.push_str(buf.format(*n));
+                    match self.timesheet.get_cell(layer, frame - 1) {
+                        Some(CellValue::Number(n)) => {
+                            let mut buf = itoa::Buffer::new();
+                            self.edit_state.editing_text.push_str(buf.format(*n));
+                        }
+                        Some(value @ CellValue::Labeled(..)) => {
+                            let _ = write!(self.edit_state.editing_text, "{}", value);
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -285,10 +388,9 @@ impl Document {
                 } else {
                     None
                 }
-            } else if let Ok(n) = self.edit_state.editing_text.trim().parse::<u32>() {
-                Some(CellValue::Number(n))
             } else {
-                None
+                // 数字或带字母后缀的编号（如 12A），0 和其他输入为空
+                CellValue::parse_drawing(&self.edit_state.editing_text).and_then(CellValue::non_blank)
             };
 
             // 检查是否有批量编辑范围
@@ -304,7 +406,7 @@ impl Document {
                         }
                         old_values.push(old_row);
                     }
-                    self.undo_stack.push_back(UndoAction::SetRange {
+                    self.push_undo(UndoAction::SetRange {
                         min_layer,
                         min_frame,
                         old_values: Rc::new(old_values),
@@ -392,6 +494,33 @@ impl Document {
         }
     }
 
+    /// 选中整张表（所有列、所有帧），选中格移到左上角；表格为空时返回 false
+    pub fn select_all(&mut self) -> bool {
+        let total_frames = self.timesheet.total_frames();
+        if total_frames == 0 || self.timesheet.layer_count == 0 {
+            return false;
+        }
+        self.set_selection((0, 0), (self.timesheet.layer_count - 1, total_frames - 1));
+        true
+    }
+
+    /// 选中整列，选中格移到该列第一帧；越界或表格为空时返回 false
+    pub fn select_layer(&mut self, layer: usize) -> bool {
+        let total_frames = self.timesheet.total_frames();
+        if total_frames == 0 || layer >= self.timesheet.layer_count {
+            return false;
+        }
+        self.set_selection((layer, 0), (layer, total_frames - 1));
+        true
+    }
+
+    fn set_selection(&mut self, start: (usize, usize), end: (usize, usize)) {
+        self.selection_state.selected_cell = Some(start);
+        self.selection_state.selection_start = Some(start);
+        self.selection_state.selection_end = Some(end);
+        self.selection_state.is_dragging = false;
+    }
+
     #[inline]
     pub fn copy_selection(&mut self, ctx: &egui::Context) {
         let range = self.get_selection_range();
@@ -402,29 +531,33 @@ impl Document {
 
             // 预分配容量以减少内存重新分配
             let mut clipboard_data = Vec::with_capacity(row_count);
-            let mut clipboard_text = String::with_capacity(row_count * col_count * 4);
-
             for layer in min_layer..=max_layer {
-                let mut row = Vec::with_capacity(col_count);
-
-                for frame in min_frame..=max_frame {
-                    let cell = self.timesheet.get_cell(layer, frame).copied();
-                    row.push(cell);
+                let row: Vec<Option<CellValue>> = (min_frame..=max_frame)
+                    .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+                    .collect();
+                clipboard_data.push(row);
+            }
 
-                    if frame > min_frame {
+            // 文本与表格布局一致（与 Excel 相同）：每行一帧，列之间用 tab 分隔
+            let mut clipboard_text = String::with_capacity(row_count * col_count * 4);
+            for frame_offset in 0..col_count {
+                for (layer_offset, row) in clipboard_data.iter().enumerate() {
+                    if layer_offset > 0 {
                         clipboard_text.push('\t');
                     }
-                    match cell {
+                    match row[frame_offset] {
                         Some(CellValue::Number(n)) => {
                             let mut buf = itoa::Buffer::new();
                             clipboard_text.push_str(buf.format(n));
                         }
+                        Some(value @ CellValue::Labeled(..)) => {
+                            let _ = write!(clipboard_text, "{}", value);
+                        }
                         Some(CellValue::Same) => clipboard_text.push('-'),
                         None => {}
                     }
                 }
-                clipboard_data.push(row);
-                if layer < max_layer {
+                if frame_offset + 1 < col_count {
                     clipboard_text.push('\n');
                 }
             }
@@ -449,7 +582,7 @@ impl Document {
                 old_values.push(old_row);
             }
 
-            self.undo_stack.push_back(UndoAction::SetRange {
+            self.push_undo(UndoAction::SetRange {
                 min_layer,
                 min_frame,
                 old_values: Rc::new(old_values),
@@ -475,7 +608,7 @@ impl Document {
                 old_values.push(old_row);
             }
 
-            self.undo_stack.push_back(UndoAction::SetRange {
+            self.push_undo(UndoAction::SetRange {
                 min_layer,
                 min_frame,
                 old_values: Rc::new(old_values),
@@ -495,84 +628,184 @@ impl Document {
         }
     }
 
-    pub fn paste_clipboard(&mut self) {
-        if let Some((start_layer, start_frame)) = self.selection_state.selected_cell {
-            if let Some(ref clipboard) = self.clipboard {
-                let mut old_values = Vec::new();
-                for (layer_offset, row) in clipboard.iter().enumerate() {
-                    let target_layer = start_layer + layer_offset;
-                    let mut old_row = Vec::new();
-                    for (frame_offset, _) in row.iter().enumerate() {
-                        let target_frame = start_frame + frame_offset;
-                        old_row.push(self.timesheet.get_cell(target_layer, target_frame).copied());
-                    }
-                    old_values.push(old_row);
-                }
+    /// 粘贴内部剪贴板到选中格，返回因超出最后一列而被丢弃的列数
+    /// 超出末尾的帧会扩展整个表格（所有列），保证 total_frames 一致
+    pub fn paste_clipboard(&mut self) -> usize {
+        match self.clipboard.clone() {
+            Some(clipboard) => self.paste_data(&clipboard),
+            None => 0,
+        }
+    }
 
-                self.undo_stack.push_back(UndoAction::SetRange {
-                    min_layer: start_layer,
-                    min_frame: start_frame,
-                    old_values: Rc::new(old_values),
-                });
-                self.is_modified = true;
-
-                for (layer_offset, row) in clipboard.iter().enumerate() {
-                    let target_layer = start_layer + layer_offset;
-                    for (frame_offset, cell) in row.iter().enumerate() {
-                        let target_frame = start_frame + frame_offset;
-                        self.timesheet.set_cell(target_layer, target_frame, *cell);
-                    }
+    /// 交换剪贴板的列 / 帧方向后粘贴（复制的一行变为一列），返回被丢弃的列数
+    pub fn paste_transposed(&mut self) -> usize {
+        let Some(clipboard) = self.clipboard.clone() else {
+            return 0;
+        };
+        let frame_count = clipboard.iter().map(Vec::len).max().unwrap_or(0);
+        let transposed: Vec<Vec<Option<CellValue>>> = (0..frame_count)
+            .map(|frame| clipboard.iter().map(|row| row.get(frame).copied().flatten()).collect())
+            .collect();
+        self.paste_data(&transposed)
+    }
+
+    /// 用剪贴板左上角的值填满选区（无选区时为选中格）：每列首格为该值，其余为 "-"
+    /// 整体记录为一次 SetRange 撤销，返回写入的单元格数
+    pub fn paste_as_hold(&mut self) -> Result<usize, &'static str> {
+        let clipboard = self.clipboard.clone().ok_or("Clipboard is empty")?;
+        let value = clipboard.first().and_then(|row| row.first()).copied().flatten();
+        let (min_layer, min_frame, max_layer, max_frame) = match self.get_selection_range() {
+            Some(range) => range,
+            None => {
+                let (layer, frame) = self.selection_state.selected_cell.ok_or("No cell selected")?;
+                (layer, frame, layer, frame)
+            }
+        };
+        let max_frame = max_frame.min(self.timesheet.total_frames().saturating_sub(1));
+        if max_layer >= self.timesheet.layer_count || min_frame > max_frame {
+            return Err("Selection is outside the sheet");
+        }
+
+        let old_values: Vec<Vec<Option<CellValue>>> = (min_layer..=max_layer)
+            .map(|layer| {
+                (min_frame..=max_frame)
+                    .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+                    .collect()
+            })
+            .collect();
+        self.push_undo(UndoAction::SetRange {
+            min_layer,
+            min_frame,
+            old_values: Rc::new(old_values),
+        });
+        self.is_modified = true;
+
+        // 空白只需清空，不写 "-"
+        let held = value.map(|_| CellValue::Same);
+        for layer in min_layer..=max_layer {
+            self.timesheet.set_cell(layer, min_frame, value);
+            for frame in min_frame + 1..=max_frame {
+                self.timesheet.set_cell(layer, frame, held);
+            }
+        }
+        Ok((max_layer - min_layer + 1) * (max_frame - min_frame + 1))
+    }
+
+    /// 将 [层][帧] 数据粘贴到选中格，返回因超出最后一列而被丢弃的列数
+    fn paste_data(&mut self, clipboard: &[Vec<Option<CellValue>>]) -> usize {
+        let Some((start_layer, start_frame)) = self.selection_state.selected_cell else {
+            return 0;
+        };
+
+        let layer_count = self.timesheet.layer_count;
+        let fit_layers = clipboard.len().min(layer_count.saturating_sub(start_layer));
+        let truncated = clipboard.len() - fit_layers;
+        if fit_layers == 0 {
+            return truncated;
+        }
+
+        let max_rows = max_frames().saturating_sub(start_frame);
+        let needed_frames = start_frame + clipboard.iter().map(|row| row.len().min(max_rows)).max().unwrap_or(0);
+
+        if needed_frames > self.timesheet.total_frames() {
+            // 帧数改变时 SetRange 无法还原长度，记录整表
+            self.push_undo(UndoAction::ReplaceCells { cells: Rc::new(self.timesheet.cells.clone()) });
+            self.timesheet.ensure_frames(needed_frames);
+        } else {
+            let mut old_values = Vec::with_capacity(fit_layers);
+            for (layer_offset, row) in clipboard.iter().take(fit_layers).enumerate() {
+                let target_layer = start_layer + layer_offset;
+                let mut old_row = Vec::new();
+                for frame_offset in 0..row.len().min(max_rows) {
+                    let target_frame = start_frame + frame_offset;
+                    old_row.push(self.timesheet.get_cell(target_layer, target_frame).copied());
                 }
+                old_values.push(old_row);
+            }
+
+            self.push_undo(UndoAction::SetRange {
+                min_layer: start_layer,
+                min_frame: start_frame,
+                old_values: Rc::new(old_values),
+            });
+        }
+        self.is_modified = true;
+
+        for (layer_offset, row) in clipboard.iter().take(fit_layers).enumerate() {
+            let target_layer = start_layer + layer_offset;
+            for (frame_offset, cell) in row.iter().take(max_rows).enumerate() {
+                let target_frame = start_frame + frame_offset;
+                self.timesheet.set_cell(target_layer, target_frame, *cell);
             }
         }
+
+        truncated
     }
 
-    /// 从文本解析剪贴板数据（tab分隔格式）
+    /// 从文本解析剪贴板数据（Excel 等表格软件的 tab 分隔格式）
+    /// 每行一帧，每个 tab 分隔的列对应一个图层；返回的数据为 [层][帧]
+    /// 数字 -> Number，"-" -> Same，空、"×" 或无法解析 -> None
     pub fn parse_clipboard_text(text: &str) -> Option<ClipboardData> {
-        let lines: Vec<&str> = text.lines().collect();
-        if lines.is_empty() {
+        let rows: Vec<Vec<Option<CellValue>>> = text
+            .lines()
+            .map(|line| {
+                line.split('\t')
+                    .map(|s| {
+                        let s = s.trim();
+                        if s == "-" {
+                            Some(CellValue::Same)
+                        } else {
+                            CellValue::parse_drawing(s).and_then(CellValue::non_blank)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        if rows.is_empty() {
             return None;
         }
 
-        let mut data = Vec::new();
-        for line in lines {
-            let row: Vec<Option<CellValue>> = line
-                .split('\t')
-                .map(|s| {
-                    let s = s.trim();
-                    if s.is_empty() {
-                        None
-                    } else if s == "-" {
-                        Some(CellValue::Same)
-                    } else {
-                        s.parse::<u32>().ok().map(CellValue::Number)
-                    }
-                })
-                .collect();
-            data.push(row);
-        }
+        // 转置为 [层][帧]，不齐的行补 None
+        let layer_count = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let data: Vec<Vec<Option<CellValue>>> = (0..layer_count)
+            .map(|layer| {
+                rows.iter()
+                    .map(|row| row.get(layer).copied().flatten())
+                    .collect()
+            })
+            .collect();
         Some(Rc::new(data))
     }
 
-    /// 从系统剪贴板文本粘贴，返回是否成功
-    pub fn paste_from_text(&mut self, text: &str) -> bool {
-        if let Some(clipboard) = Self::parse_clipboard_text(text) {
-            self.clipboard = Some(clipboard);
-            self.paste_clipboard();
-            true
-        } else {
-            false
+    /// 从系统剪贴板文本粘贴，返回被丢弃的列数；无法解析时返回 None
+    pub fn paste_from_text(&mut self, text: &str) -> Option<usize> {
+        let clipboard = Self::parse_clipboard_text(text)?;
+        self.clipboard = Some(clipboard);
+        Some(self.paste_clipboard())
+    }
+
+    /// 提交列名编辑：去除首尾空白，空名称保留原名
+    /// 返回需要在状态栏提示的信息（空名称，或 STS 无法完整保存的名称）
+    pub fn finish_layer_name_edit(&mut self) -> Option<String> {
+        let layer = self.edit_state.editing_layer_name.take()?;
+        let name = self.edit_state.editing_layer_text.trim();
+        if name.is_empty() {
+            return Some("Layer name cannot be empty".to_string());
         }
+
+        let current = self.timesheet.layer_names.get_mut(layer)?;
+        if current != name {
+            *current = name.to_string();
+            self.is_modified = true;
+        }
+        sts_rust::sts_layer_name_warning(name)
     }
 
     /// 在指定位置插入一列
     pub fn insert_layer(&mut self, index: usize) {
         self.timesheet.insert_layer(index);
-        // 限制撤销栈大小
-        if self.undo_stack.len() >= MAX_UNDO_ACTIONS {
-            self.undo_stack.pop_front();
-        }
-        self.undo_stack.push_back(UndoAction::InsertLayer { index });
+        self.push_undo(UndoAction::InsertLayer { index });
         self.is_modified = true;
 
         // 调整可能受列插入影响的状态索引
@@ -581,6 +814,203 @@ impl Document {
         self.adjust_context_menu_for_insert(index);
     }
 
+    /// 移动列（from -> to），并同步调整选择/编辑/弹窗状态中的列索引
+    pub fn move_layer(&mut self, from: usize, to: usize) -> bool {
+        if !self.timesheet.move_layer(from, to) {
+            return false;
+        }
+        self.push_undo(UndoAction::MoveLayer { from, to });
+        self.is_modified = true;
+        self.adjust_state_for_move(from, to);
+        true
+    }
+
+    /// 列从 from 移动到 to 后，调整选择/编辑/弹窗状态中的列索引（撤销/重做同样适用）
+    fn adjust_state_for_move(&mut self, from: usize, to: usize) {
+        let map = |layer: usize| -> usize {
+            if layer == from {
+                to
+            } else if from < to && layer > from && layer <= to {
+                layer - 1
+            } else if to < from && layer >= to && layer < from {
+                layer + 1
+            } else {
+                layer
+            }
+        };
+
+        if let Some((layer, frame)) = self.selection_state.selected_cell {
+            self.selection_state.selected_cell = Some((map(layer), frame));
+        }
+        if let Some((layer, frame)) = self.selection_state.selection_start {
+            self.selection_state.selection_start = Some((map(layer), frame));
+        }
+        if let Some((layer, frame)) = self.selection_state.selection_end {
+            self.selection_state.selection_end = Some((map(layer), frame));
+        }
+        if let Some((layer, frame)) = self.edit_state.editing_cell {
+            self.edit_state.editing_cell = Some((map(layer), frame));
+        }
+        if let Some(layer) = self.edit_state.editing_layer_name {
+            self.edit_state.editing_layer_name = Some(map(layer));
+        }
+        self.context_menu.pos = None;
+        self.context_menu.selection = None;
+        self.repeat_dialog.layer = map(self.repeat_dialog.layer);
+        self.sequence_fill_dialog.layer = map(self.sequence_fill_dialog.layer);
+        for scope in [&mut self.replace_dialog.scope, &mut self.offset_dialog.scope] {
+            if let ReplaceScope::Layer(layer) = *scope {
+                *scope = ReplaceScope::Layer(map(layer));
+            }
+        }
+    }
+
+    /// 在指定位置插入一帧（所有列）
+    pub fn insert_frame(&mut self, at: usize) -> bool {
+        let old_cells = Rc::new(self.timesheet.cells.clone());
+        if !self.timesheet.insert_frame(at) {
+            return false;
+        }
+        self.push_undo(UndoAction::ReplaceCells { cells: old_cells });
+        self.is_modified = true;
+        self.edit_state.editing_cell = None;
+        self.edit_state.editing_text.clear();
+        true
+    }
+
+    /// 删除指定位置的帧（所有列），后续帧上移
+    pub fn delete_frame(&mut self, at: usize) -> bool {
+        let old_cells = Rc::new(self.timesheet.cells.clone());
+        if !self.timesheet.delete_frame(at) {
+            return false;
+        }
+        self.push_undo(UndoAction::ReplaceCells { cells: old_cells });
+        self.is_modified = true;
+        self.edit_state.editing_cell = None;
+        self.edit_state.editing_text.clear();
+
+        // 选中格超出范围时移到最后一帧
+        let last_frame = self.timesheet.total_frames().saturating_sub(1);
+        if let Some((layer, frame)) = self.selection_state.selected_cell {
+            if frame > last_frame {
+                self.selection_state.selected_cell = Some((layer, last_frame));
+            }
+        }
+        self.selection_state.selection_start = None;
+        self.selection_state.selection_end = None;
+        true
+    }
+
+    /// 将整表调整为 frame_count 帧（所有列），整表快照记录为一次撤销
+    /// 返回被截断丢弃的非空单元格数量
+    pub fn set_duration(&mut self, frame_count: usize) -> Result<usize, &'static str> {
+        if frame_count == 0 {
+            return Err("Duration must be at least 1 frame");
+        }
+        if frame_count > max_frames() {
+            return Err("Duration exceeds the maximum frame count");
+        }
+        if frame_count == self.timesheet.total_frames() {
+            return Ok(0);
+        }
+
+        let discarded = self.timesheet.occupied_from(frame_count);
+        self.push_undo(UndoAction::ReplaceCells { cells: Rc::new(self.timesheet.cells.clone()) });
+        self.timesheet.set_total_frames(frame_count);
+        self.is_modified = true;
+
+        // 编辑 / 选中位置超出新长度时收回到最后一帧
+        if matches!(self.edit_state.editing_cell, Some((_, frame)) if frame >= frame_count) {
+            self.edit_state.editing_cell = None;
+            self.edit_state.editing_text.clear();
+        }
+        let last_frame = frame_count - 1;
+        if let Some((layer, frame)) = self.selection_state.selected_cell {
+            if frame > last_frame {
+                self.selection_state.selected_cell = Some((layer, last_frame));
+            }
+        }
+        let selection_out_of_range = [self.selection_state.selection_start, self.selection_state.selection_end]
+            .iter()
+            .any(|cell| matches!(cell, Some((_, frame)) if *frame > last_frame));
+        if selection_out_of_range {
+            self.selection_state.selection_start = None;
+            self.selection_state.selection_end = None;
+        }
+        Ok(discarded)
+    }
+
+    /// 在单列的 at 处插入 count 帧，该列后续单元格下移，其他列不变
+    /// 插入的格子延续上一帧（有值时为 "-"，否则为空）；下移的内容超出总帧数时扩展表格
+    pub fn insert_rows(&mut self, layer: usize, at: usize, count: usize) -> bool {
+        let total_frames = self.timesheet.total_frames();
+        if layer >= self.timesheet.layer_count || at >= total_frames || count == 0 {
+            return false;
+        }
+
+        let held = if at > 0 && self.timesheet.get_actual_value(layer, at - 1).is_some() {
+            Some(CellValue::Same)
+        } else {
+            None
+        };
+        let old_row: Vec<Option<CellValue>> = (at..total_frames)
+            .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+            .collect();
+        let mut new_row = vec![held; count];
+        new_row.extend_from_slice(&old_row);
+
+        // 需要的帧数：最后一个非空格之后即可截断
+        let required = at + new_row.iter().rposition(Option::is_some).map_or(0, |i| i + 1);
+        if required > total_frames {
+            if required > max_frames() {
+                return false;
+            }
+            // 帧数改变时 SetRange 无法还原长度，记录整表
+            self.push_undo(UndoAction::ReplaceCells { cells: Rc::new(self.timesheet.cells.clone()) });
+            self.timesheet.ensure_frames(required);
+        } else {
+            new_row.truncate(total_frames - at);
+            self.push_undo(UndoAction::SetRange {
+                min_layer: layer,
+                min_frame: at,
+                old_values: Rc::new(vec![old_row]),
+            });
+        }
+        self.is_modified = true;
+
+        for (offset, value) in new_row.into_iter().enumerate() {
+            self.timesheet.set_cell(layer, at + offset, value);
+        }
+        true
+    }
+
+    /// 删除单列 at 处起的 count 帧，该列后续单元格上移，末尾补空，其他列不变
+    pub fn delete_rows(&mut self, layer: usize, at: usize, count: usize) -> bool {
+        let total_frames = self.timesheet.total_frames();
+        if layer >= self.timesheet.layer_count || at >= total_frames || count == 0 {
+            return false;
+        }
+
+        let count = count.min(total_frames - at);
+        let old_row: Vec<Option<CellValue>> = (at..total_frames)
+            .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+            .collect();
+        let mut new_row = old_row[count..].to_vec();
+        new_row.resize(old_row.len(), None);
+
+        self.push_undo(UndoAction::SetRange {
+            min_layer: layer,
+            min_frame: at,
+            old_values: Rc::new(vec![old_row]),
+        });
+        self.is_modified = true;
+
+        for (offset, value) in new_row.into_iter().enumerate() {
+            self.timesheet.set_cell(layer, at + offset, value);
+        }
+        true
+    }
+
     /// 调整选择状态的索引（列插入后）
     fn adjust_selection_for_insert(&mut self, inserted_index: usize) {
         // 调整选中的单元格索引
@@ -639,11 +1069,7 @@ impl Document {
     /// 删除指定位置的列
     pub fn delete_layer(&mut self, index: usize) {
         if let Some((name, cells)) = self.timesheet.delete_layer(index) {
-            // 限制撤销栈大小
-            if self.undo_stack.len() >= MAX_UNDO_ACTIONS {
-                self.undo_stack.pop_front();
-            }
-            self.undo_stack.push_back(UndoAction::DeleteLayer { index, name, cells });
+            self.push_undo(UndoAction::DeleteLayer { index, name, cells });
             self.is_modified = true;
 
             // 清理可能指向被删除列的状态
@@ -708,45 +1134,91 @@ impl Document {
         }
     }
 
-    pub fn undo(&mut self) {
-        if let Some(action) = self.undo_stack.pop_back() {
-            match action {
-                UndoAction::SetCell { layer, frame, old_value } => {
-                    self.timesheet.set_cell(layer, frame, old_value);
-                }
-                UndoAction::SetRange { min_layer, min_frame, old_values } => {
-                    for (layer_offset, row) in old_values.iter().enumerate() {
-                        for (frame_offset, value) in row.iter().enumerate() {
-                            self.timesheet.set_cell(
-                                min_layer + layer_offset,
-                                min_frame + frame_offset,
-                                *value,
-                            );
-                        }
+    /// 执行撤销/重做动作，返回其逆操作
+    fn apply_undo_action(&mut self, action: UndoAction) -> UndoAction {
+        match action {
+            UndoAction::SetCell { layer, frame, old_value } => {
+                let current = self.timesheet.get_cell(layer, frame).copied();
+                self.timesheet.set_cell(layer, frame, old_value);
+                UndoAction::SetCell { layer, frame, old_value: current }
+            }
+            UndoAction::SetRange { min_layer, min_frame, old_values } => {
+                let mut current_values = Vec::with_capacity(old_values.len());
+                for (layer_offset, row) in old_values.iter().enumerate() {
+                    let mut current_row = Vec::with_capacity(row.len());
+                    for (frame_offset, value) in row.iter().enumerate() {
+                        let layer = min_layer + layer_offset;
+                        let frame = min_frame + frame_offset;
+                        current_row.push(self.timesheet.get_cell(layer, frame).copied());
+                        self.timesheet.set_cell(layer, frame, *value);
                     }
+                    current_values.push(current_row);
                 }
-                UndoAction::InsertLayer { index } => {
-                    // 撤销插入 = 删除该列（不记录撤销）
-                    let _ = self.timesheet.delete_layer(index);
-                }
-                UndoAction::DeleteLayer { index, name, cells } => {
-                    // 撤销删除 = 恢复该列
-                    self.timesheet.cells.insert(index, cells);
-                    self.timesheet.layer_names.insert(index, name);
-                    self.timesheet.layer_count += 1;
+                UndoAction::SetRange { min_layer, min_frame, old_values: Rc::new(current_values) }
+            }
+            UndoAction::InsertLayer { index } => {
+                // 撤销插入 = 删除该列（不记录撤销）
+                let (name, cells) = self.timesheet.delete_layer(index).unwrap_or_default();
+                UndoAction::DeleteLayer { index, name, cells }
+            }
+            UndoAction::DeleteLayer { index, name, cells } => {
+                // 撤销删除 = 恢复该列
+                self.timesheet.cells.insert(index, cells.into());
+                self.timesheet.layer_names.insert(index, name);
+                self.timesheet.layer_count += 1;
+                UndoAction::InsertLayer { index }
+            }
+            UndoAction::MoveLayer { from, to } => {
+                // 撤销移动 = 反向移动
+                if self.timesheet.move_layer(to, from) {
+                    self.adjust_state_for_move(to, from);
                 }
+                UndoAction::MoveLayer { from: to, to: from }
+            }
+            UndoAction::ReplaceCells { cells } => {
+                let current = std::mem::replace(&mut self.timesheet.cells, (*cells).clone());
+                UndoAction::ReplaceCells { cells: Rc::new(current) }
             }
+        }
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(action) = self.undo_stack.pop_back() {
+            let inverse = self.apply_undo_action(action);
+            if self.redo_stack.len() >= MAX_UNDO_ACTIONS {
+                self.redo_stack.pop_front();
+            }
+            self.redo_stack.push_back(inverse);
             self.is_modified = true;
         }
     }
 
+    pub fn redo(&mut self) {
+        if let Some(action) = self.redo_stack.pop_back() {
+            let inverse = self.apply_undo_action(action);
+            // 直接入撤销栈，不清空重做栈
+            if self.undo_stack.len() >= MAX_UNDO_ACTIONS {
+                self.undo_stack.pop_front();
+            }
+            self.undo_stack.push_back(inverse);
+            self.is_modified = true;
+        }
+    }
+
+    /// 记录一个新的撤销动作（新的编辑会清空重做栈）
     #[inline]
-    pub fn push_undo_set_cell(&mut self, layer: usize, frame: usize, old_value: Option<CellValue>) {
+    pub fn push_undo(&mut self, action: UndoAction) {
         // 限制撤销栈大小
         if self.undo_stack.len() >= MAX_UNDO_ACTIONS {
             self.undo_stack.pop_front();
         }
-        self.undo_stack.push_back(UndoAction::SetCell {
+        self.undo_stack.push_back(action);
+        self.redo_stack.clear();
+    }
+
+    #[inline]
+    pub fn push_undo_set_cell(&mut self, layer: usize, frame: usize, old_value: Option<CellValue>) {
+        self.push_undo(UndoAction::SetCell {
             layer,
             frame,
             old_value,
@@ -763,12 +1235,16 @@ impl Document {
                     std::mem::size_of::<UndoAction>() +
                     old_values.len() * old_values.first().map_or(0, |row| row.len() * std::mem::size_of::<Option<CellValue>>())
                 }
-                UndoAction::InsertLayer { .. } => std::mem::size_of::<UndoAction>(),
+                UndoAction::InsertLayer { .. } | UndoAction::MoveLayer { .. } => std::mem::size_of::<UndoAction>(),
                 UndoAction::DeleteLayer { cells, name, .. } => {
                     std::mem::size_of::<UndoAction>() +
                     cells.len() * std::mem::size_of::<Option<CellValue>>() +
                     name.len()
                 }
+                UndoAction::ReplaceCells { cells } => {
+                    std::mem::size_of::<UndoAction>() +
+                    cells.iter().map(|layer| layer.occupied() * std::mem::size_of::<(usize, CellValue)>()).sum::<usize>()
+                }
             }
         }).sum()
     }
@@ -825,7 +1301,7 @@ impl Document {
         }
         old_values.push(old_row);
 
-        self.undo_stack.push_back(UndoAction::SetRange {
+        self.push_undo(UndoAction::SetRange {
             min_layer: layer,
             min_frame: insert_start,
             old_values: Rc::new(old_values),
@@ -899,7 +1375,7 @@ impl Document {
         }
         old_values.push(old_row);
 
-        self.undo_stack.push_back(UndoAction::SetRange {
+        self.push_undo(UndoAction::SetRange {
             min_layer: layer,
             min_frame: insert_start,
             old_values: Rc::new(old_values),
@@ -914,6 +1390,117 @@ impl Document {
         Ok(())
     }
 
+    /// 将单列选区中等于前一帧实际值的数字改为 Same（"-"），返回修改的单元格数
+    pub fn numbers_to_holds(&mut self) -> Result<usize, &'static str> {
+        let (layer, start_frame, end_frame) = self.check_single_column_selection()?;
+        Ok(self.rewrite_column_range(layer, start_frame, end_frame, |ts, frame| {
+            match ts.get_cell(layer, frame) {
+                Some(value @ (CellValue::Number(_) | CellValue::Labeled(..)))
+                    if frame > 0 && ts.get_actual_cell(layer, frame - 1) == Some(*value) =>
+                {
+                    Some(Some(CellValue::Same))
+                }
+                _ => None,
+            }
+        }))
+    }
+
+    /// 将单列选区中的 Same 改为其实际数字，返回修改的单元格数
+    pub fn holds_to_numbers(&mut self) -> Result<usize, &'static str> {
+        let (layer, start_frame, end_frame) = self.check_single_column_selection()?;
+        Ok(self.rewrite_column_range(layer, start_frame, end_frame, |ts, frame| {
+            match ts.get_cell(layer, frame) {
+                Some(CellValue::Same) => ts.get_actual_cell(layer, frame).map(Some),
+                _ => None,
+            }
+        }))
+    }
+
+    /// 从选中格（有选区时为选区底部）起，将每列当前的值保持到最后一帧
+    /// 整体记录为一次 SetRange 撤销，返回改写的单元格数
+    pub fn fill_down(&mut self) -> Result<usize, &'static str> {
+        let (min_layer, max_layer, from) = match self.get_selection_range() {
+            Some((min_layer, _, max_layer, max_frame)) => (min_layer, max_layer, max_frame),
+            None => {
+                let (layer, frame) = self.selection_state.selected_cell.ok_or("No cell selected")?;
+                (layer, layer, frame)
+            }
+        };
+        let total_frames = self.timesheet.total_frames();
+        if max_layer >= self.timesheet.layer_count || from >= total_frames {
+            return Err("Selection is outside the sheet");
+        }
+        if (min_layer..=max_layer).all(|layer| self.timesheet.get_actual_value(layer, from).is_none()) {
+            return Err("Nothing to fill down: the cell is empty");
+        }
+        if from + 1 == total_frames {
+            return Ok(0);
+        }
+
+        let old_values: Vec<Vec<Option<CellValue>>> = (min_layer..=max_layer)
+            .map(|layer| {
+                (from + 1..total_frames)
+                    .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+                    .collect()
+            })
+            .collect();
+        self.push_undo(UndoAction::SetRange {
+            min_layer,
+            min_frame: from + 1,
+            old_values: Rc::new(old_values),
+        });
+        self.is_modified = true;
+
+        let mut changed = 0;
+        for layer in min_layer..=max_layer {
+            // 空白列保持不变
+            if self.timesheet.get_actual_value(layer, from).is_none() {
+                continue;
+            }
+            for frame in from + 1..total_frames {
+                if self.timesheet.get_cell(layer, frame) != Some(&CellValue::Same) {
+                    self.timesheet.set_cell(layer, frame, Some(CellValue::Same));
+                    changed += 1;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// 按 rewrite 的结果改写一列中的单元格（返回 None 表示不修改），
+    /// 新值全部基于修改前的表格计算，整体记录为一次 SetRange 撤销
+    fn rewrite_column_range(
+        &mut self,
+        layer: usize,
+        start_frame: usize,
+        end_frame: usize,
+        rewrite: impl Fn(&TimeSheet, usize) -> Option<Option<CellValue>>,
+    ) -> usize {
+        let end_frame = end_frame.min(self.timesheet.total_frames().saturating_sub(1));
+        let changes: Vec<(usize, Option<CellValue>)> = (start_frame..=end_frame)
+            .filter_map(|frame| rewrite(&self.timesheet, frame).map(|value| (frame, value)))
+            .collect();
+
+        let (Some(&(first, _)), Some(&(last, _))) = (changes.first(), changes.last()) else {
+            return 0;
+        };
+
+        let old_row: Vec<Option<CellValue>> = (first..=last)
+            .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+            .collect();
+        self.push_undo(UndoAction::SetRange {
+            min_layer: layer,
+            min_frame: first,
+            old_values: Rc::new(vec![old_row]),
+        });
+        self.is_modified = true;
+
+        for &(frame, value) in &changes {
+            self.timesheet.set_cell(layer, frame, value);
+        }
+        changes.len()
+    }
+
     /// 执行序列填充操作
     /// 从 start_value 到 end_value，每个数字重复 hold_frames 帧
     /// 例如：start=1, end=5, hold=2 -> 1122334455
@@ -951,7 +1538,7 @@ impl Document {
         }
         old_values.push(old_row);
 
-        self.undo_stack.push_back(UndoAction::SetRange {
+        self.push_undo(UndoAction::SetRange {
             min_layer: layer,
             min_frame: start_frame,
             old_values: Rc::new(old_values),
@@ -982,66 +1569,298 @@ impl Document {
         Ok(())
     }
 
-    /// Generate AE Time Remap keyframe data for entire column and copy to clipboard
-    /// version: AE keyframe version string like "6.0", "7.0", "8.0", "9.0"
-    pub fn copy_ae_keyframes(&self, ctx: &egui::Context, layer: usize, version: &str) -> Result<(), &'static str> {
-        if layer >= self.timesheet.layer_count {
-            return Err("Invalid layer");
+    /// 作用范围对应的 (min_layer, min_frame, max_layer, max_frame)，max_frame 可能超出表格末尾；
+    /// 没有选区或列越界时返回 None
+    fn scope_range(&self, scope: ReplaceScope) -> Option<(usize, usize, usize, usize)> {
+        let layer_count = self.timesheet.layer_count;
+        let (min_layer, min_frame, max_layer, max_frame) = match scope {
+            ReplaceScope::Selection => self.get_selection_range()?,
+            ReplaceScope::Layer(layer) => (layer, 0, layer, usize::MAX),
+            ReplaceScope::Sheet => (0, 0, layer_count.saturating_sub(1), usize::MAX),
+        };
+        if layer_count == 0 || min_layer >= layer_count {
+            return None;
+        }
+        Some((min_layer, min_frame, max_layer.min(layer_count - 1), max_frame))
+    }
+
+    /// 以改动单元格的包围盒保存旧值，记录为一次 SetRange 撤销
+    fn push_undo_for_cells(&mut self, cells: &[(usize, usize)]) {
+        let Some(min_layer) = cells.iter().map(|c| c.0).min() else {
+            return;
+        };
+        let max_layer = cells.iter().map(|c| c.0).max().unwrap();
+        let min_frame = cells.iter().map(|c| c.1).min().unwrap();
+        let max_frame = cells.iter().map(|c| c.1).max().unwrap();
+
+        let mut old_values = Vec::with_capacity(max_layer - min_layer + 1);
+        for layer in min_layer..=max_layer {
+            let mut old_row = Vec::with_capacity(max_frame - min_frame + 1);
+            for frame in min_frame..=max_frame {
+                old_row.push(self.timesheet.get_cell(layer, frame).copied());
+            }
+            old_values.push(old_row);
+        }
+
+        self.push_undo(UndoAction::SetRange {
+            min_layer,
+            min_frame,
+            old_values: Rc::new(old_values),
+        });
+    }
+
+    /// 将范围内所有编号 from 替换为 to，带后缀的编号保留后缀（12A -> 7A），返回替换的单元格数
+    /// to 为 0 时按空白处理；Same 单元格继承前值，不做处理；整个替换记录为一次 SetRange 撤销
+    pub fn replace_value(&mut self, scope: ReplaceScope, from: u32, to: u32) -> usize {
+        let Some((min_layer, min_frame, max_layer, max_frame)) = self.scope_range(scope) else {
+            return 0;
+        };
+        if from == to {
+            return 0;
+        }
+
+        // 找出需要替换的单元格
+        let mut matches = Vec::new();
+        for layer in min_layer..=max_layer {
+            let frame_end = max_frame.min(self.timesheet.cells[layer].len().saturating_sub(1));
+            for frame in min_frame..=frame_end {
+                let new_value = match self.timesheet.get_cell(layer, frame) {
+                    Some(&CellValue::Number(n)) if n == from => CellValue::Number(to),
+                    Some(&CellValue::Labeled(n, suffix)) if n == from => CellValue::Labeled(to, suffix),
+                    _ => continue,
+                };
+                matches.push((layer, frame, new_value));
+            }
+        }
+
+        if matches.is_empty() {
+            return 0;
         }
 
-        let framerate = self.timesheet.framerate as f64;
-        let frame_count = self.timesheet.total_frames();
-        let mut keyframe_text = String::with_capacity(1024);
+        let cells: Vec<(usize, usize)> = matches.iter().map(|&(layer, frame, _)| (layer, frame)).collect();
+        self.push_undo_for_cells(&cells);
+        self.is_modified = true;
 
-        // AE keyframe header (use \r\n for Windows clipboard compatibility)
-        keyframe_text.push_str("Adobe After Effects ");
-        keyframe_text.push_str(version);
-        keyframe_text.push_str(" Keyframe Data\r\n\r\n");
-        keyframe_text.push_str("\tUnits Per Second\t");
-        keyframe_text.push_str(&(framerate as u32).to_string());
-        keyframe_text.push_str("\r\n\tSource Width\t1000\r\n\tSource Height\t1000\r\n");
-        keyframe_text.push_str("\tSource Pixel Aspect Ratio\t1\r\n\tComp Pixel Aspect Ratio\t1\r\n\r\n");
+        for &(layer, frame, value) in &matches {
+            self.timesheet.set_cell(layer, frame, value.non_blank());
+        }
 
-        // Time Remap effect
-        keyframe_text.push_str("Time Remap\r\n");
-        keyframe_text.push_str("\tFrame\tseconds\t\r\n");
+        matches.len()
+    }
 
-        // Collect keyframes (only when value changes)
-        let mut prev_value: Option<u32> = None;
+    /// 范围内所有编号加上 delta（保留字母后缀），结果最小为 0，0 按空白处理；
+    /// Same 和空白不变，整体记录为一次 SetRange 撤销，返回改动的单元格数
+    pub fn offset_values(&mut self, scope: ReplaceScope, delta: i32) -> Result<usize, &'static str> {
+        let (min_layer, min_frame, max_layer, max_frame) = self.scope_range(scope).ok_or("No selection")?;
+        if delta == 0 {
+            return Ok(0);
+        }
+        let offset = |n: u32| n.saturating_add_signed(delta);
+
+        let mut changes = Vec::new();
+        for layer in min_layer..=max_layer {
+            let frame_end = max_frame.min(self.timesheet.cells[layer].len().saturating_sub(1));
+            for frame in min_frame..=frame_end {
+                let new_value = match self.timesheet.get_cell(layer, frame) {
+                    Some(&CellValue::Number(n)) => CellValue::Number(offset(n)),
+                    Some(&CellValue::Labeled(n, suffix)) => CellValue::Labeled(offset(n), suffix),
+                    _ => continue,
+                };
+                if self.timesheet.get_cell(layer, frame) != Some(&new_value) {
+                    changes.push((layer, frame, new_value.non_blank()));
+                }
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let cells: Vec<(usize, usize)> = changes.iter().map(|&(layer, frame, _)| (layer, frame)).collect();
+        self.push_undo_for_cells(&cells);
+        self.is_modified = true;
+
+        for &(layer, frame, value) in &changes {
+            self.timesheet.set_cell(layer, frame, value);
+        }
+
+        Ok(changes.len())
+    }
+
+    /// 按出现顺序将该列的关键帧重新编号为 start, start+1, ...
+    /// 例如 1 4 4 9 -> 1 2 2 3；Same 与空白不变，关键帧数量保持不变
+    pub fn renumber_layer(&mut self, layer: usize, start: u32) -> Result<(), &'static str> {
+        if layer >= self.timesheet.layer_count {
+            return Err("Invalid layer");
+        }
+
+        let old_row: Vec<Option<CellValue>> = self.timesheet.cells[layer].to_vec();
 
-        for frame in 0..frame_count {
-            let current_value = self.timesheet.get_actual_value(layer, frame);
-
-            // Output keyframe when value changes
-            if current_value != prev_value {
-                // Frame number in timeline
-                keyframe_text.push('\t');
-                keyframe_text.push_str(&frame.to_string());
-                keyframe_text.push('\t');
-
-                if let Some(value) = current_value {
-                    // Time Remap value: convert cell value to seconds
-                    // Cell value 1 = frame 0 in source = 0 seconds
-                    let time_seconds = (value.saturating_sub(1)) as f64 / framerate;
-                    // Format with 7 decimal places (AE uses 7)
-                    if time_seconds == 0.0 {
-                        keyframe_text.push_str("0");
-                    } else {
-                        // Remove trailing zeros from formatted number
-                        let formatted = format!("{:.7}", time_seconds);
-                        let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
-                        keyframe_text.push_str(trimmed);
+        // 计算新值：与前一实际值不同的数字开始新的关键帧
+        let mut new_row = old_row.clone();
+        let mut prev_value: Option<u32> = None;
+        let mut next_number = start;
+        let mut current_number = start;
+        let mut keyframe_count = 0usize;
+        for (frame, cell) in old_row.iter().enumerate() {
+            match cell {
+                Some(CellValue::Number(n)) => {
+                    if prev_value != Some(*n) {
+                        current_number = next_number;
+                        next_number = next_number.checked_add(1).ok_or("Drawing number overflow")?;
+                        keyframe_count += 1;
                     }
-                } else {
-                    // Empty cell - output 0
-                    keyframe_text.push_str("0");
+                    prev_value = Some(*n);
+                    new_row[frame] = Some(CellValue::Number(current_number));
                 }
-                keyframe_text.push_str("\t\r\n");
-                prev_value = current_value;
+                // 带后缀的编号与同一数字归为一组，保留后缀（12 12A 13 -> 1 1A 2）
+                Some(CellValue::Labeled(n, suffix)) => {
+                    if prev_value != Some(*n) {
+                        current_number = next_number;
+                        next_number = next_number.checked_add(1).ok_or("Drawing number overflow")?;
+                        keyframe_count += 1;
+                    }
+                    prev_value = Some(*n);
+                    new_row[frame] = Some(CellValue::Labeled(current_number, *suffix));
+                }
+                Some(CellValue::Same) => {}
+                None => prev_value = None,
+            }
+        }
+
+        if keyframe_count == 0 {
+            return Err("No drawing numbers to renumber");
+        }
+        if new_row == old_row {
+            return Ok(());
+        }
+
+        self.push_undo(UndoAction::SetRange {
+            min_layer: layer,
+            min_frame: 0,
+            old_values: Rc::new(vec![old_row]),
+        });
+        self.is_modified = true;
+
+        for (frame, value) in new_row.into_iter().enumerate() {
+            self.timesheet.set_cell(layer, frame, value);
+        }
+
+        Ok(())
+    }
+
+    /// 将整列平移 delta 帧（正数延后，负数提前），总帧数不变
+    /// 延后时开头补空白；提前时末尾按最后的实际值补 Same（无值则补空白）
+    pub fn shift_layer(&mut self, layer: usize, delta: i32) -> bool {
+        if layer >= self.timesheet.layer_count || delta == 0 {
+            return false;
+        }
+
+        let total_frames = self.timesheet.total_frames();
+        let old_row: Vec<Option<CellValue>> = (0..total_frames)
+            .map(|frame| self.timesheet.get_cell(layer, frame).copied())
+            .collect();
+        let offset = (delta.unsigned_abs() as usize).min(total_frames);
+
+        let mut new_row: Vec<Option<CellValue>> = Vec::with_capacity(total_frames);
+        if delta > 0 {
+            new_row.resize(offset, None);
+            new_row.extend_from_slice(&old_row[..total_frames - offset]);
+        } else {
+            new_row.extend_from_slice(&old_row[offset..]);
+            // 移到第一帧的 Same 失去了前值，转换为实际数字
+            if let Some(Some(CellValue::Same)) = new_row.first() {
+                new_row[0] = self.timesheet.get_actual_cell(layer, offset);
+            }
+            let tail = if self.timesheet.get_actual_value(layer, total_frames.saturating_sub(1)).is_some() {
+                Some(CellValue::Same)
+            } else {
+                None
+            };
+            new_row.resize(total_frames, tail);
+            if offset == total_frames {
+                new_row.fill(None);
             }
         }
 
-        keyframe_text.push_str("\r\nEnd of Keyframe Data\r\n");
+        if new_row == old_row {
+            return false;
+        }
+
+        self.push_undo(UndoAction::SetRange {
+            min_layer: layer,
+            min_frame: 0,
+            old_values: Rc::new(vec![old_row]),
+        });
+        self.is_modified = true;
+
+        for (frame, value) in new_row.into_iter().enumerate() {
+            self.timesheet.set_cell(layer, frame, value);
+        }
+
+        true
+    }
+
+    /// 跳转到指定页或帧："p3" 跳到第 3 页第 1 帧，"120" 跳到第 120 帧（均从 1 开始）
+    /// 保持当前选中的列，并滚动到选中格
+    pub fn go_to(&mut self, input: &str) -> Result<(), &'static str> {
+        let input = input.trim();
+        let frame = if let Some(page) = input.strip_prefix(['p', 'P']) {
+            let page: usize = page.trim().parse().map_err(|_| "Invalid page number")?;
+            if page == 0 {
+                return Err("Invalid page number");
+            }
+            (page - 1).saturating_mul(self.timesheet.frames_per_page as usize)
+        } else {
+            let frame: usize = input.parse().map_err(|_| "Invalid frame number")?;
+            if frame == 0 {
+                return Err("Invalid frame number");
+            }
+            frame - 1
+        };
+
+        if frame >= self.timesheet.total_frames() {
+            return Err("Frame is out of range");
+        }
+
+        let layer = self.selection_state.selected_cell.map_or(0, |(layer, _)| layer);
+        self.selection_state.selected_cell = Some((layer, frame));
+        self.selection_state.selection_start = Some((layer, frame));
+        self.selection_state.selection_end = Some((layer, frame));
+        self.selection_state.auto_scroll_to_selection = true;
+        Ok(())
+    }
+
+    /// 清空整表所有单元格（保留帧数），原单元格记录为一次 ReplaceCells 撤销
+    pub fn clear_all_cells(&mut self) {
+        let empty = self.timesheet.cells.iter()
+            .map(|layer| LayerCells::new(layer.len()))
+            .collect();
+        let old_cells = std::mem::replace(&mut self.timesheet.cells, empty);
+        self.push_undo(UndoAction::ReplaceCells { cells: Rc::new(old_cells) });
+
+        self.is_modified = true;
+        self.edit_state.editing_cell = None;
+        self.edit_state.editing_text.clear();
+    }
+
+    /// Generate AE Time Remap keyframe data for entire column and copy to clipboard
+    /// version: AE keyframe version string like "6.0", "7.0", "8.0", "9.0"
+    pub fn copy_ae_keyframes(&self, ctx: &egui::Context, layer: usize, version: &str) -> Result<(), &'static str> {
+        let keyframe_text = sts_rust::format_ae_keyframes(&self.timesheet, layer, version)
+            .map_err(|_| "Invalid layer")?;
+
+        // Copy to system clipboard
+        ctx.output_mut(|o| o.copied_text = keyframe_text);
+
+        Ok(())
+    }
+
+    /// Generate AE Time Remap keyframe data for a range of columns (one block per layer)
+    pub fn copy_ae_keyframes_range(&self, ctx: &egui::Context, min_layer: usize, max_layer: usize, version: &str) -> Result<(), &'static str> {
+        let layers: Vec<usize> = (min_layer..=max_layer).collect();
+        let keyframe_text = sts_rust::format_ae_keyframes_for_layers(&self.timesheet, &layers, version)
+            .map_err(|_| "Invalid layer")?;
 
         // Copy to system clipboard
         ctx.output_mut(|o| o.copied_text = keyframe_text);
@@ -1049,3 +1868,610 @@ impl Document {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_doc() -> Document {
+        Document::new(0, TimeSheet::new("test".to_string(), 24, 3, 144), None)
+    }
+
+    #[test]
+    fn test_undo_redo_set_cell() {
+        let mut doc = new_doc();
+        doc.start_edit(0, 0);
+        doc.edit_state.editing_text = "5".to_string();
+        doc.finish_edit(false, true);
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(5)));
+
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), None);
+
+        doc.redo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(5)));
+
+        // 重做后还能再次撤销
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), None);
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut doc = new_doc();
+        doc.push_undo_set_cell(0, 0, None);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.undo();
+        assert_eq!(doc.redo_stack.len(), 1);
+
+        doc.push_undo_set_cell(0, 1, None);
+        assert!(doc.redo_stack.is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo_layer_ops() {
+        let mut doc = new_doc();
+        doc.timesheet.set_cell(1, 0, Some(CellValue::Number(7)));
+        doc.delete_layer(1);
+        assert_eq!(doc.timesheet.layer_count, 2);
+
+        doc.undo();
+        assert_eq!(doc.timesheet.layer_count, 3);
+        assert_eq!(doc.timesheet.get_cell(1, 0), Some(&CellValue::Number(7)));
+
+        doc.redo();
+        assert_eq!(doc.timesheet.layer_count, 2);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(1, 0), Some(&CellValue::Number(7)));
+    }
+
+    #[test]
+    fn test_insert_delete_frame_undo() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(3);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Number(2)));
+
+        assert!(doc.insert_frame(1));
+        assert_eq!(doc.timesheet.total_frames(), 4);
+        doc.undo();
+        assert_eq!(doc.timesheet.total_frames(), 3);
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Number(2)));
+
+        assert!(doc.delete_frame(0));
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(2)));
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+
+        assert!(!doc.delete_frame(3));
+    }
+
+    #[test]
+    fn test_move_layer_adjusts_state() {
+        let mut doc = new_doc();
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.selection_state.selected_cell = Some((0, 0));
+        doc.repeat_dialog.layer = 2;
+
+        assert!(doc.move_layer(0, 2));
+        assert_eq!(doc.timesheet.get_cell(2, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 0)));
+        assert_eq!(doc.repeat_dialog.layer, 1);
+
+        doc.replace_dialog.scope = ReplaceScope::Layer(2);
+
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.layer_names, vec!["A", "B", "C"]);
+        assert_eq!(doc.selection_state.selected_cell, Some((0, 0)));
+        assert_eq!(doc.repeat_dialog.layer, 2);
+        assert_eq!(doc.replace_dialog.scope, ReplaceScope::Layer(0));
+
+        doc.redo();
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 0)));
+        assert_eq!(doc.replace_dialog.scope, ReplaceScope::Layer(2));
+    }
+
+    #[test]
+    fn test_parse_excel_grid() {
+        // 3 帧 x 2 列，含 "-"、"×"、空格和无法解析的内容
+        let text = "1\t5\r\n-\t\r\n×\tabc\r\n";
+        let data = Document::parse_clipboard_text(text).unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0], vec![Some(CellValue::Number(1)), Some(CellValue::Same), None]);
+        assert_eq!(data[1], vec![Some(CellValue::Number(5)), None, None]);
+
+        // 不齐的行
+        let data = Document::parse_clipboard_text("1\n2\t3\n").unwrap();
+        assert_eq!(data[1], vec![None, Some(CellValue::Number(3))]);
+    }
+
+    #[test]
+    fn test_paste_from_text_single_undo() {
+        let mut doc = new_doc();
+        doc.selection_state.selected_cell = Some((1, 2));
+        assert_eq!(doc.paste_from_text("7\t8\n9\t"), Some(0));
+
+        assert_eq!(doc.timesheet.get_cell(1, 2), Some(&CellValue::Number(7)));
+        assert_eq!(doc.timesheet.get_cell(2, 2), Some(&CellValue::Number(8)));
+        assert_eq!(doc.timesheet.get_cell(1, 3), Some(&CellValue::Number(9)));
+        assert_eq!(doc.undo_stack.len(), 1);
+
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(1, 2), None);
+        assert_eq!(doc.timesheet.get_cell(2, 2), None);
+    }
+
+    #[test]
+    fn test_paste_past_right_edge() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(2);
+        doc.selection_state.selected_cell = Some((2, 1));
+
+        // 4 列 x 3 帧，从最后一列开始粘贴：丢弃 3 列，帧数扩展到 4
+        assert_eq!(doc.paste_from_text("1\t2\t3\t4\n5\t6\t7\t8\n9\t10\t11\t12"), Some(3));
+        assert_eq!(doc.timesheet.get_cell(2, 1), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(2, 3), Some(&CellValue::Number(9)));
+        assert_eq!(doc.timesheet.total_frames(), 4);
+        assert!(doc.timesheet.cells.iter().all(|layer| layer.len() == 4));
+
+        // 撤销同时恢复原帧数
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(2, 1), None);
+        assert_eq!(doc.timesheet.total_frames(), 2);
+        assert!(doc.timesheet.cells.iter().all(|layer| layer.len() == 2));
+
+        doc.redo();
+        assert_eq!(doc.timesheet.get_cell(2, 3), Some(&CellValue::Number(9)));
+        assert_eq!(doc.timesheet.total_frames(), 4);
+    }
+
+    #[test]
+    fn test_replace_value_scopes() {
+        let mut doc = new_doc();
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(5)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Same));
+        doc.timesheet.set_cell(0, 4, Some(CellValue::Number(5)));
+        doc.timesheet.set_cell(2, 2, Some(CellValue::Number(5)));
+        doc.timesheet.set_cell(1, 3, Some(CellValue::Number(6)));
+
+        // 单列
+        assert_eq!(doc.replace_value(ReplaceScope::Layer(0), 5, 12), 2);
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(12)));
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Same));
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(12)));
+        assert_eq!(doc.timesheet.get_cell(2, 2), Some(&CellValue::Number(5)));
+
+        // 选区
+        doc.selection_state.selection_start = Some((1, 0));
+        doc.selection_state.selection_end = Some((2, 1));
+        assert_eq!(doc.replace_value(ReplaceScope::Selection, 5, 7), 0);
+
+        // 整表，一次撤销全部恢复
+        assert_eq!(doc.replace_value(ReplaceScope::Sheet, 12, 5), 2);
+        assert_eq!(doc.undo_stack.len(), 2);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(12)));
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(12)));
+        assert_eq!(doc.timesheet.get_cell(1, 3), Some(&CellValue::Number(6)));
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Same));
+
+        // 带后缀的编号保留后缀
+        doc.timesheet.set_cell(1, 0, Some(CellValue::Labeled(5, 'A')));
+        assert_eq!(doc.replace_value(ReplaceScope::Layer(1), 5, 9), 1);
+        assert_eq!(doc.timesheet.get_cell(1, 0), Some(&CellValue::Labeled(9, 'A')));
+
+        // 替换为 0 即清空
+        assert_eq!(doc.replace_value(ReplaceScope::Sheet, 6, 0), 1);
+        assert_eq!(doc.timesheet.get_cell(1, 3), None);
+    }
+
+    #[test]
+    fn test_offset_values() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(4);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Same));
+        doc.timesheet.set_cell(0, 2, Some(CellValue::Labeled(3, 'A')));
+        doc.timesheet.set_cell(1, 3, Some(CellValue::Number(5)));
+
+        // 整表，Same 和空白不变
+        assert_eq!(doc.offset_values(ReplaceScope::Sheet, 100), Ok(3));
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(101)));
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Same));
+        assert_eq!(doc.timesheet.get_cell(0, 2), Some(&CellValue::Labeled(103, 'A')));
+        assert_eq!(doc.timesheet.get_cell(0, 3), None);
+        assert_eq!(doc.timesheet.get_cell(1, 3), Some(&CellValue::Number(105)));
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(1, 3), Some(&CellValue::Number(5)));
+
+        // 单列，减到 0 以下按空白处理
+        assert_eq!(doc.offset_values(ReplaceScope::Layer(1), -10), Ok(1));
+        assert_eq!(doc.timesheet.get_cell(1, 3), None);
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        doc.undo();
+
+        // 选区
+        doc.selection_state.selection_start = Some((0, 1));
+        doc.selection_state.selection_end = Some((0, 2));
+        assert_eq!(doc.offset_values(ReplaceScope::Selection, -2), Ok(1));
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(0, 2), Some(&CellValue::Labeled(1, 'A')));
+        assert_eq!(doc.undo_stack.len(), 1);
+
+        assert_eq!(doc.offset_values(ReplaceScope::Sheet, 0), Ok(0));
+        doc.selection_state.selection_start = None;
+        doc.selection_state.selection_end = None;
+        assert!(doc.offset_values(ReplaceScope::Selection, 1).is_err());
+    }
+
+    #[test]
+    fn test_renumber_layer() {
+        let mut doc = new_doc();
+        let values = [
+            Some(CellValue::Number(1)),
+            Some(CellValue::Number(4)),
+            Some(CellValue::Same),
+            Some(CellValue::Number(4)),
+            None,
+            Some(CellValue::Number(9)),
+            Some(CellValue::Number(1)),
+        ];
+        for (frame, value) in values.iter().enumerate() {
+            doc.timesheet.set_cell(0, frame, *value);
+        }
+
+        doc.renumber_layer(0, 1).unwrap();
+        let renumbered: Vec<_> = (0..values.len()).map(|f| doc.timesheet.get_cell(0, f).copied()).collect();
+        assert_eq!(renumbered, vec![
+            Some(CellValue::Number(1)),
+            Some(CellValue::Number(2)),
+            Some(CellValue::Same),
+            Some(CellValue::Number(2)),
+            None,
+            Some(CellValue::Number(3)),
+            Some(CellValue::Number(4)),
+        ]);
+
+        doc.undo();
+        let restored: Vec<_> = (0..values.len()).map(|f| doc.timesheet.get_cell(0, f).copied()).collect();
+        assert_eq!(restored, values.to_vec());
+
+        assert!(doc.renumber_layer(1, 1).is_err());
+        assert!(doc.renumber_layer(5, 1).is_err());
+    }
+
+    #[test]
+    fn test_shift_layer() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(12);
+        let total = doc.timesheet.total_frames();
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Same));
+        doc.timesheet.set_cell(0, 2, Some(CellValue::Number(2)));
+        doc.timesheet.set_cell(0, total - 1, Some(CellValue::Number(3)));
+
+        // 延后：开头补空白，末尾被截掉
+        assert!(doc.shift_layer(0, 2));
+        assert_eq!(doc.timesheet.get_cell(0, 0), None);
+        assert_eq!(doc.timesheet.get_cell(0, 2), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(2)));
+        assert_eq!(doc.timesheet.get_cell(0, total - 1), None);
+        assert_eq!(doc.timesheet.total_frames(), total);
+
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(0, total - 1), Some(&CellValue::Number(3)));
+
+        // 提前：首帧的 Same 转为实际值，末尾按持续值补 Same
+        assert!(doc.shift_layer(0, -1));
+        assert_eq!(doc.timesheet.get_cell(0, 0), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Number(2)));
+        assert_eq!(doc.timesheet.get_cell(0, total - 2), Some(&CellValue::Number(3)));
+        assert_eq!(doc.timesheet.get_cell(0, total - 1), Some(&CellValue::Same));
+
+        assert!(!doc.shift_layer(1, 3));
+    }
+
+    #[test]
+    fn test_batch_edit_single_undo() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(4);
+        doc.timesheet.set_cell(1, 2, Some(CellValue::Number(9)));
+
+        // 2 列 x 3 帧的选区
+        doc.selection_state.selection_start = Some((0, 0));
+        doc.selection_state.selection_end = Some((1, 2));
+        doc.start_batch_edit(0, 0);
+        doc.edit_state.editing_text = "4".to_string();
+        doc.finish_edit(false, true);
+
+        for layer in 0..2 {
+            for frame in 0..3 {
+                assert_eq!(doc.timesheet.get_cell(layer, frame), Some(&CellValue::Number(4)));
+            }
+        }
+        assert_eq!(doc.undo_stack.len(), 1);
+
+        doc.undo();
+        for layer in 0..2 {
+            for frame in 0..3 {
+                let expected = if (layer, frame) == (1, 2) { Some(&CellValue::Number(9)) } else { None };
+                assert_eq!(doc.timesheet.get_cell(layer, frame), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clear_all_cells_undo() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Same));
+        doc.timesheet.set_cell(2, 5, Some(CellValue::Number(3)));
+        let before = doc.timesheet.cells.clone();
+
+        doc.clear_all_cells();
+        assert!(doc.timesheet.cells.iter().all(|layer| layer.occupied() == 0));
+        assert_eq!(doc.timesheet.total_frames(), 6);
+        assert_eq!(doc.undo_stack.len(), 1);
+
+        doc.undo();
+        assert_eq!(doc.timesheet.cells, before);
+    }
+
+    #[test]
+    fn test_go_to() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(300);
+        doc.selection_state.selected_cell = Some((2, 5));
+
+        assert!(doc.go_to("120").is_ok());
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 119)));
+        assert!(doc.selection_state.auto_scroll_to_selection);
+
+        // 每页 144 帧
+        assert!(doc.go_to("p3").is_ok());
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 288)));
+
+        assert!(doc.go_to("p4").is_err());
+        assert!(doc.go_to("0").is_err());
+        assert!(doc.go_to("abc").is_err());
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 288)));
+    }
+
+    #[test]
+    fn test_finish_layer_name_edit() {
+        let mut doc = new_doc();
+
+        doc.edit_state.editing_layer_name = Some(1);
+        doc.edit_state.editing_layer_text = "  BG  ".to_string();
+        assert_eq!(doc.finish_layer_name_edit(), None);
+        assert_eq!(doc.timesheet.layer_names[1], "BG");
+        assert!(doc.is_modified);
+
+        // 空名称保留原名
+        doc.edit_state.editing_layer_name = Some(1);
+        doc.edit_state.editing_layer_text = "   ".to_string();
+        assert!(doc.finish_layer_name_edit().is_some());
+        assert_eq!(doc.timesheet.layer_names[1], "BG");
+        assert_eq!(doc.edit_state.editing_layer_name, None);
+
+        // STS 无法保存的名称仍然接受，但返回警告
+        doc.edit_state.editing_layer_name = Some(0);
+        doc.edit_state.editing_layer_text = "A".repeat(300);
+        assert!(doc.finish_layer_name_edit().is_some());
+        assert_eq!(doc.timesheet.layer_names[0].len(), 300);
+    }
+
+    #[test]
+    fn test_numbers_holds_toggle() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        // 1 1 - 2 2 (空)
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 2, Some(CellValue::Same));
+        doc.timesheet.set_cell(0, 3, Some(CellValue::Number(2)));
+        doc.timesheet.set_cell(0, 4, Some(CellValue::Number(2)));
+        doc.selection_state.selection_start = Some((0, 0));
+        doc.selection_state.selection_end = Some((0, 5));
+
+        assert_eq!(doc.numbers_to_holds(), Ok(2));
+        let expected = [Some(CellValue::Number(1)), Some(CellValue::Same), Some(CellValue::Same),
+            Some(CellValue::Number(2)), Some(CellValue::Same), None];
+        for (frame, value) in expected.iter().enumerate() {
+            assert_eq!(doc.timesheet.get_cell(0, frame).copied(), *value);
+        }
+
+        assert_eq!(doc.holds_to_numbers(), Ok(3));
+        let expected = [1, 1, 1, 2, 2];
+        for (frame, n) in expected.iter().enumerate() {
+            assert_eq!(doc.timesheet.get_cell(0, frame), Some(&CellValue::Number(*n)));
+        }
+
+        // 每次操作一次撤销
+        assert_eq!(doc.undo_stack.len(), 2);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 2), Some(&CellValue::Same));
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Same));
+
+        doc.selection_state.selection_end = Some((1, 5));
+        assert!(doc.numbers_to_holds().is_err());
+    }
+
+    #[test]
+    fn test_insert_delete_rows() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        // 列 0: 1 - 2 (空) (空) 3，列 1: 5
+        for (frame, value) in [(0, CellValue::Number(1)), (1, CellValue::Same), (2, CellValue::Number(2)), (5, CellValue::Number(3))] {
+            doc.timesheet.set_cell(0, frame, Some(value));
+        }
+        doc.timesheet.set_cell(1, 1, Some(CellValue::Number(5)));
+        let column = |doc: &Document| (0..doc.timesheet.total_frames())
+            .map(|frame| doc.timesheet.get_cell(0, frame).copied())
+            .collect::<Vec<_>>();
+        let original = column(&doc);
+
+        // 末尾有值，需要扩展表格
+        assert!(doc.insert_rows(0, 2, 2));
+        assert_eq!(doc.timesheet.total_frames(), 8);
+        assert_eq!(column(&doc), vec![
+            Some(CellValue::Number(1)), Some(CellValue::Same), Some(CellValue::Same), Some(CellValue::Same),
+            Some(CellValue::Number(2)), None, None, Some(CellValue::Number(3)),
+        ]);
+        assert_eq!(doc.timesheet.get_cell(1, 1), Some(&CellValue::Number(5)));
+
+        doc.undo();
+        assert_eq!(doc.timesheet.total_frames(), 6);
+        assert_eq!(column(&doc), original);
+
+        assert!(doc.delete_rows(0, 1, 2));
+        assert_eq!(column(&doc), vec![
+            Some(CellValue::Number(1)), None, None, Some(CellValue::Number(3)), None, None,
+        ]);
+        doc.undo();
+        assert_eq!(column(&doc), original);
+
+        // 尾部为空时不扩展
+        doc.timesheet.set_cell(0, 5, None);
+        assert!(doc.insert_rows(0, 3, 2));
+        assert_eq!(doc.timesheet.total_frames(), 6);
+        assert_eq!(doc.timesheet.get_cell(0, 3), Some(&CellValue::Same));
+
+        assert!(!doc.insert_rows(3, 0, 1));
+        assert!(!doc.delete_rows(0, 6, 1));
+    }
+
+    #[test]
+    fn test_set_duration() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(0, 4, Some(CellValue::Number(2)));
+        doc.timesheet.set_cell(1, 5, Some(CellValue::Same));
+        doc.selection_state.selected_cell = Some((1, 5));
+
+        assert_eq!(doc.timesheet.occupied_from(4), 2);
+        assert_eq!(doc.set_duration(4), Ok(2));
+        assert_eq!(doc.timesheet.total_frames(), 4);
+        assert!(doc.timesheet.cells.iter().all(|layer| layer.len() == 4));
+        assert_eq!(doc.timesheet.get_cell(0, 4), None);
+        assert_eq!(doc.selection_state.selected_cell, Some((1, 3)));
+
+        doc.undo();
+        assert_eq!(doc.timesheet.total_frames(), 6);
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(2)));
+        assert_eq!(doc.timesheet.get_cell(1, 5), Some(&CellValue::Same));
+
+        assert_eq!(doc.set_duration(10), Ok(0));
+        assert!(doc.timesheet.cells.iter().all(|layer| layer.len() == 10));
+        doc.undo();
+        assert_eq!(doc.timesheet.total_frames(), 6);
+
+        assert!(doc.set_duration(0).is_err());
+        assert!(doc.set_duration(max_frames() + 1).is_err());
+    }
+
+    #[test]
+    fn test_fill_down() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(3)));
+        doc.timesheet.set_cell(0, 1, Some(CellValue::Same));
+        doc.timesheet.set_cell(0, 4, Some(CellValue::Number(5)));
+        doc.timesheet.set_cell(2, 2, Some(CellValue::Labeled(7, 'A')));
+
+        // 单格：从选中格保持到最后一帧
+        doc.selection_state.selected_cell = Some((0, 1));
+        assert_eq!(doc.fill_down(), Ok(4));
+        assert!((2..6).all(|frame| doc.timesheet.get_cell(0, frame) == Some(&CellValue::Same)));
+        assert_eq!(doc.timesheet.get_actual_value(0, 5), Some(3));
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(5)));
+        assert_eq!(doc.timesheet.get_cell(0, 2), None);
+
+        // 选区：从选区底部开始，空白列不变
+        doc.selection_state.selection_start = Some((0, 0));
+        doc.selection_state.selection_end = Some((2, 2));
+        assert_eq!(doc.fill_down(), Ok(3));
+        assert_eq!(doc.timesheet.get_actual_cell(2, 5), Some(CellValue::Labeled(7, 'A')));
+        assert_eq!(doc.timesheet.get_cell(0, 4), Some(&CellValue::Number(5)));
+        assert_eq!(doc.timesheet.get_cell(1, 5), None);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(2, 3), None);
+
+        doc.selection_state.selection_start = Some((1, 0));
+        doc.selection_state.selection_end = Some((1, 0));
+        assert!(doc.fill_down().is_err());
+    }
+
+    #[test]
+    fn test_select_all_and_layer() {
+        let mut doc = new_doc();
+        assert!(!doc.select_all());
+        doc.timesheet.ensure_frames(4);
+        doc.timesheet.set_cell(0, 0, Some(CellValue::Number(1)));
+        doc.timesheet.set_cell(2, 3, Some(CellValue::Number(2)));
+
+        assert!(doc.select_all());
+        let last_layer = doc.timesheet.layer_count - 1;
+        assert_eq!(doc.get_selection_range(), Some((0, 0, last_layer, 3)));
+        assert_eq!(doc.selection_state.selected_cell, Some((0, 0)));
+        assert!(doc.check_single_column_selection().is_err());
+
+        doc.delete_selection();
+        assert_eq!(doc.timesheet.get_cell(0, 0), None);
+        assert_eq!(doc.timesheet.get_cell(2, 3), None);
+        assert_eq!(doc.timesheet.total_frames(), 4);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(2, 3), Some(&CellValue::Number(2)));
+
+        assert!(doc.select_layer(2));
+        assert_eq!(doc.check_single_column_selection(), Ok((2, 0, 3)));
+        assert_eq!(doc.selection_state.selected_cell, Some((2, 0)));
+        assert!(!doc.select_layer(last_layer + 1));
+        assert_eq!(doc.get_selection_range(), Some((2, 0, 2, 3)));
+    }
+
+    #[test]
+    fn test_paste_special() {
+        let mut doc = new_doc();
+        doc.timesheet.ensure_frames(6);
+        doc.clipboard = Some(Rc::new(vec![
+            vec![Some(CellValue::Number(1))],
+            vec![Some(CellValue::Number(2))],
+            vec![Some(CellValue::Same)],
+        ]));
+
+        // 一行三列转置为一列三帧
+        doc.selection_state.selected_cell = Some((0, 1));
+        assert_eq!(doc.paste_transposed(), 0);
+        assert_eq!(doc.timesheet.get_cell(0, 1), Some(&CellValue::Number(1)));
+        assert_eq!(doc.timesheet.get_cell(0, 2), Some(&CellValue::Number(2)));
+        assert_eq!(doc.timesheet.get_cell(0, 3), Some(&CellValue::Same));
+        assert_eq!(doc.timesheet.get_cell(1, 1), None);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(0, 2), None);
+
+        // 左上角的值在选区中保持
+        doc.timesheet.set_cell(2, 3, Some(CellValue::Number(9)));
+        doc.selection_state.selection_start = Some((1, 1));
+        doc.selection_state.selection_end = Some((2, 4));
+        assert_eq!(doc.paste_as_hold(), Ok(8));
+        for layer in 1..=2 {
+            assert_eq!(doc.timesheet.get_cell(layer, 1), Some(&CellValue::Number(1)));
+            assert!((2..=4).all(|frame| doc.timesheet.get_cell(layer, frame) == Some(&CellValue::Same)));
+        }
+        assert_eq!(doc.timesheet.get_cell(1, 5), None);
+        assert_eq!(doc.undo_stack.len(), 1);
+        doc.undo();
+        assert_eq!(doc.timesheet.get_cell(2, 3), Some(&CellValue::Number(9)));
+        assert_eq!(doc.timesheet.get_cell(1, 1), None);
+
+        doc.clipboard = None;
+        assert!(doc.paste_as_hold().is_err());
+    }
+}
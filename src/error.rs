@@ -0,0 +1,69 @@
+//! Error type for the library
+//!
+//! All parse/write functions in [`crate::formats`] return [`StsError`].
+//! It implements `std::error::Error`, so `?` converts it into `anyhow::Error`.
+
+use thiserror::Error;
+
+/// Errors returned by the format parsers and writers
+#[derive(Debug, Error)]
+pub enum StsError {
+    /// File could not be read or written
+    #[error("{context}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// File does not start with the expected magic / header
+    #[error("{0}")]
+    InvalidSignature(String),
+
+    /// File version is not supported
+    #[error("Unsupported {format} version {version}")]
+    UnsupportedVersion { format: &'static str, version: u32 },
+
+    /// Layer count exceeds the limit of the format or of the library
+    #[error("Too many layers: {found} (max: {max})")]
+    TooManyLayers { found: usize, max: usize },
+
+    /// Frame count exceeds the limit of the format or of the library
+    #[error("Too many frames: {found} (max: {max})")]
+    TooManyFrames { found: usize, max: usize },
+
+    /// JSON based formats (XDTS/TDTS/JSON) could not be (de)serialized
+    #[error("{context}")]
+    Json {
+        context: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// File content is malformed or could not be decoded
+    #[error("{0}")]
+    Decode(String),
+
+    /// Input data cannot be represented in the target format
+    #[error("{0}")]
+    InvalidData(String),
+}
+
+/// Result type used by the library
+pub type Result<T> = std::result::Result<T, StsError>;
+
+impl From<std::io::Error> for StsError {
+    fn from(source: std::io::Error) -> Self {
+        StsError::Io { context: format!("I/O error: {}", source), source }
+    }
+}
+
+impl StsError {
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        StsError::Io { context: context.into(), source }
+    }
+
+    pub(crate) fn json(context: impl Into<String>, source: serde_json::Error) -> Self {
+        StsError::Json { context: context.into(), source }
+    }
+}
@@ -0,0 +1,196 @@
+//! After Effects ExtendScript (.jsx) writer
+//!
+//! 生成的脚本在 AE 中运行（File > Scripts > Run Script File）后会：
+//! - 按 `source_width`/`source_height`、`comp_pixel_aspect_ratio` 和帧率新建合成；
+//! - 每列新建一个占位合成（使用 `source_pixel_aspect_ratio`）并加入主合成，
+//!   开启 Time Remap，在每个变化点写入定格关键帧；空白段用不透明度 0 隐藏。
+//!
+//! AE 的纯色层和空对象不能开启 Time Remap，因此使用占位合成，
+//! 替换为实际的原画序列即可。
+
+use std::fmt::Write as _;
+
+use crate::error::{Result, StsError};
+use crate::models::timesheet::TimeSheet;
+
+/// AE 合成宽高的有效范围
+const AE_MIN_COMP_SIZE: u32 = 4;
+const AE_MAX_COMP_SIZE: u32 = 30000;
+
+/// 脚本主体：读取开头的 `data` 对象
+const SCRIPT_BODY: &str = r#"
+    app.beginUndoGroup("Create " + data.name);
+
+    var frameDuration = 1 / data.frameRate;
+    var duration = data.frames * frameDuration;
+    var comp = app.project.items.addComp(data.name, data.width, data.height, data.pixelAspect, duration, data.frameRate);
+
+    // 从最后一列开始添加，使第一列位于最上方
+    for (var i = data.layers.length - 1; i >= 0; i--) {
+        var info = data.layers[i];
+        var maxDrawing = 1;
+        for (var k = 0; k < info.keys.length; k++) {
+            if (info.keys[k][1] !== null) {
+                maxDrawing = Math.max(maxDrawing, info.keys[k][1]);
+            }
+        }
+
+        // 占位合成：替换为实际素材即可
+        var source = app.project.items.addComp(info.name, data.width, data.height, data.sourcePixelAspect, maxDrawing * frameDuration, data.frameRate);
+        var layer = comp.layers.add(source);
+        layer.timeRemapEnabled = true;
+        layer.outPoint = duration;
+
+        var timeRemap = layer.property("ADBE Time Remapping");
+        var opacity = layer.property("ADBE Transform Group").property("ADBE Opacity");
+        while (timeRemap.numKeys > 0) {
+            timeRemap.removeKey(timeRemap.numKeys);
+        }
+
+        var lastDrawing = 1;
+        for (var k = 0; k < info.keys.length; k++) {
+            var time = info.keys[k][0] * frameDuration;
+            var drawing = info.keys[k][1];
+            if (drawing !== null) {
+                lastDrawing = drawing;
+            }
+
+            // 编号 1 = 素材第 0 帧
+            var remapKey = timeRemap.addKey(time);
+            timeRemap.setValueAtKey(remapKey, Math.max(lastDrawing - 1, 0) * frameDuration);
+            timeRemap.setInterpolationTypeAtKey(remapKey, KeyframeInterpolationType.HOLD);
+
+            var opacityKey = opacity.addKey(time);
+            opacity.setValueAtKey(opacityKey, drawing !== null ? 100 : 0);
+            opacity.setInterpolationTypeAtKey(opacityKey, KeyframeInterpolationType.HOLD);
+        }
+    }
+
+    comp.openInViewer();
+    app.endUndoGroup();
+})();
+"#;
+
+/// Quote a string as a JavaScript literal; non-ASCII is escaped so the script
+/// runs regardless of the encoding AE assumes for the file
+fn js_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            ' '..='~' => quoted.push(c),
+            _ => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(quoted, "\\u{:04x}", unit);
+                }
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Generate an ExtendScript that builds the comp and time-remapped layers
+pub fn format_ae_jsx(timesheet: &TimeSheet) -> Result<String> {
+    let frame_count = timesheet.total_frames();
+    if frame_count == 0 {
+        return Err(StsError::InvalidData("Timesheet has no frames".to_string()));
+    }
+    if timesheet.framerate == 0 {
+        return Err(StsError::InvalidData("Framerate must be greater than 0".to_string()));
+    }
+    for (label, size) in [("width", timesheet.source_width), ("height", timesheet.source_height)] {
+        if !(AE_MIN_COMP_SIZE..=AE_MAX_COMP_SIZE).contains(&size) {
+            return Err(StsError::InvalidData(format!(
+                "Source {} {} is outside the After Effects range {}-{}",
+                label, size, AE_MIN_COMP_SIZE, AE_MAX_COMP_SIZE
+            )));
+        }
+    }
+    for ratio in [timesheet.source_pixel_aspect_ratio, timesheet.comp_pixel_aspect_ratio] {
+        if !(ratio.is_finite() && ratio > 0.0) {
+            return Err(StsError::InvalidData(format!("Invalid pixel aspect ratio: {}", ratio)));
+        }
+    }
+
+    let mut script = String::with_capacity(4096);
+    let _ = writeln!(script, "// After Effects script generated from timesheet {}", js_string(&timesheet.name));
+    script.push_str("(function () {\n");
+    script.push_str("    var data = {\n");
+    let _ = writeln!(script, "        name: {},", js_string(&timesheet.name));
+    let _ = writeln!(script, "        width: {},", timesheet.source_width);
+    let _ = writeln!(script, "        height: {},", timesheet.source_height);
+    let _ = writeln!(script, "        pixelAspect: {},", timesheet.comp_pixel_aspect_ratio);
+    let _ = writeln!(script, "        sourcePixelAspect: {},", timesheet.source_pixel_aspect_ratio);
+    let _ = writeln!(script, "        frameRate: {},", timesheet.framerate);
+    let _ = writeln!(script, "        frames: {},", frame_count);
+    script.push_str("        layers: [\n");
+    for layer in 0..timesheet.layer_count {
+        // 关键帧：[帧号, 编号]，空白为 null；带后缀的编号取数字部分
+        let keys: Vec<String> = timesheet.keyframes(layer)
+            .map(|(frame, value)| match value {
+                Some(n) => format!("[{}, {}]", frame, n),
+                None => format!("[{}, null]", frame),
+            })
+            .collect();
+        let _ = writeln!(
+            script,
+            "            {{ name: {}, keys: [{}] }},",
+            js_string(&timesheet.layer_names[layer]),
+            keys.join(", ")
+        );
+    }
+    script.push_str("        ]\n");
+    script.push_str("    };\n");
+    script.push_str(SCRIPT_BODY);
+
+    Ok(script)
+}
+
+/// Write an After Effects ExtendScript (.jsx) for a TimeSheet
+pub fn write_ae_jsx(timesheet: &TimeSheet, path: &str) -> Result<()> {
+    let script = format_ae_jsx(timesheet)?;
+    std::fs::write(path, script)
+        .map_err(|e| StsError::io(format!("Failed to write JSX file: {}", path), e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timesheet::CellValue;
+
+    #[test]
+    fn test_js_string() {
+        assert_eq!(js_string(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(js_string("原画\n"), r#""\u539f\u753b\u000a""#);
+        assert_eq!(js_string("😀"), r#""\ud83d\ude00""#);
+    }
+
+    #[test]
+    fn test_format_ae_jsx() {
+        let mut ts = TimeSheet::new("cut 01".to_string(), 24, 2, 144);
+        ts.ensure_frames(6);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Number(3)));
+        ts.set_cell(1, 4, Some(CellValue::Number(2)));
+        ts.source_width = 1920;
+        ts.source_height = 1080;
+        ts.source_pixel_aspect_ratio = 0.9;
+
+        let script = format_ae_jsx(&ts).unwrap();
+        assert!(script.contains("        width: 1920,\n        height: 1080,\n        pixelAspect: 1,\n        sourcePixelAspect: 0.9,\n"));
+        assert!(script.contains("        frameRate: 24,\n        frames: 6,\n"));
+        assert!(script.contains(r#"{ name: "A", keys: [[0, 1], [2, 3], [3, null]] },"#));
+        assert!(script.contains(r#"{ name: "B", keys: [[0, null], [4, 2], [5, null]] },"#));
+        assert!(script.trim_end().ends_with("})();"));
+
+        ts.source_width = 2;
+        assert!(matches!(format_ae_jsx(&ts), Err(StsError::InvalidData(_))));
+        assert!(format_ae_jsx(&TimeSheet::new("empty".to_string(), 24, 1, 144)).is_err());
+    }
+}
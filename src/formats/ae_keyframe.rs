@@ -1,14 +1,311 @@
-use anyhow::{Result, bail};
+use crate::error::{Result, StsError};
 use crate::models::TimeSheet;
+use crate::models::timesheet::CellValue;
+use crate::limits::max_frames;
+use super::fill_keyframes;
 
 /// 解析 After Effects 关键帧文件
 ///
-pub fn parse_ae_keyframe_file(_path: &str) -> Result<TimeSheet> {
-    bail!("Use native STS format instead.")
+/// 读取第一个关键帧数据块（Time Remap / Opacity / Slider 等），生成单图层的 TimeSheet。
+/// Time Remap 的秒数按帧率换算为原画号（0 秒 = 1 号），其他属性直接四舍五入为原画号。
+pub fn parse_ae_keyframe_file(path: &str) -> Result<TimeSheet> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| StsError::io(format!("Failed to read AE keyframe file: {}", path), e))?;
+
+    let mut lines = content.lines().map(|l| l.trim_end_matches('\r'));
+
+    // Header: "Adobe After Effects x.x Keyframe Data"
+    let header = lines.by_ref().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let header = header.trim();
+    if !header.starts_with("Adobe After Effects") || !header.ends_with("Keyframe Data") {
+        return Err(StsError::InvalidSignature(format!("Invalid AE keyframe header: {:?}", header)));
+    }
+
+    let mut framerate: Option<f64> = None;
+    let mut stream_name: Option<String> = None;
+    let mut keyframes: Vec<(usize, f64)> = Vec::new();
+
+    for line in lines {
+        if line.trim() == "End of Keyframe Data" {
+            break;
+        }
+        if line.trim().is_empty() {
+            // 空行结束当前数据块（只读取第一个）
+            if stream_name.is_some() && !keyframes.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+
+        if !line.starts_with('\t') {
+            // Stream name line, e.g. "Time Remap" or "Transform\tOpacity"
+            if stream_name.is_none() {
+                stream_name = Some(fields.iter().filter(|f| !f.is_empty()).copied().collect::<Vec<_>>().join(" "));
+            }
+            continue;
+        }
+
+        match fields.get(1).copied() {
+            Some("Units Per Second") => {
+                let fps = fields.get(2)
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|v| *v > 0.0)
+                    .ok_or_else(|| StsError::Decode(format!("Invalid frame rate line: {:?}", line)))?;
+                framerate = Some(fps);
+            }
+            Some("Frame") => {} // Column header
+            Some(frame_str) if stream_name.is_some() => {
+                let frame = frame_str.parse::<f64>()
+                    .map_err(|_| StsError::Decode(format!("Invalid keyframe row: {:?}", line)))?;
+                let value = fields.get(2)
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .ok_or_else(|| StsError::Decode(format!("Invalid keyframe row: {:?}", line)))?;
+                if frame < 0.0 {
+                    return Err(StsError::Decode(format!("Negative keyframe frame: {}", frame)));
+                }
+                keyframes.push((frame.round() as usize, value));
+            }
+            _ => {} // Source Width / Height / Pixel Aspect Ratio etc.
+        }
+    }
+
+    let stream_name = stream_name
+        .ok_or_else(|| StsError::Decode("No keyframe data found in AE keyframe file".to_string()))?;
+    if keyframes.is_empty() {
+        return Err(StsError::Decode(format!("No keyframes found in stream: {}", stream_name)));
+    }
+
+    let framerate = framerate.unwrap_or(24.0);
+    let is_time_remap = stream_name.contains("Time Remap");
+
+    keyframes.sort_by_key(|k| k.0);
+    let frame_count = keyframes.last().map(|k| k.0 + 1).unwrap_or(0);
+    if frame_count > max_frames() {
+        return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
+    }
+
+    let cell_keyframes: Vec<(usize, Option<CellValue>)> = keyframes.iter()
+        .map(|&(frame, value)| {
+            let drawing = if is_time_remap {
+                (value * framerate).round() + 1.0
+            } else {
+                value.round()
+            };
+            let cell = if drawing >= 1.0 {
+                Some(CellValue::Number(drawing as u32))
+            } else {
+                None
+            };
+            (frame, cell)
+        })
+        .collect();
+
+    let file_name = std::path::Path::new(path)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("untitled");
+
+    let mut timesheet = TimeSheet::new(
+        file_name.to_string(),
+        framerate.round() as u32,
+        1,
+        144, // Default frames per page
+    );
+    timesheet.ensure_frames(frame_count);
+    timesheet.layer_names[0] = stream_name;
+    fill_keyframes(&mut timesheet, 0, &cell_keyframes, frame_count);
+
+    Ok(timesheet)
+}
+
+/// 生成单个图层的 AE Time Remap 关键帧文本（可直接粘贴到 AE 时间轴）
+///
+/// version: AE keyframe version string like "6.0", "7.0", "8.0", "9.0"
+pub fn format_ae_keyframes(timesheet: &TimeSheet, layer: usize, version: &str) -> Result<String> {
+    format_ae_keyframes_for_layers(timesheet, &[layer], version)
+}
+
+/// 生成多个图层的 AE Time Remap 关键帧文本
+///
+/// 共用一个文件头，每个图层一个 Time Remap 数据块，块之间以空行分隔。
+pub fn format_ae_keyframes_for_layers(timesheet: &TimeSheet, layers: &[usize], version: &str) -> Result<String> {
+    if layers.is_empty() {
+        return Err(StsError::InvalidData("No layers to export".to_string()));
+    }
+    if let Some(&layer) = layers.iter().find(|&&l| l >= timesheet.layer_count) {
+        return Err(StsError::InvalidData(format!("Invalid layer: {} (layer count: {})", layer, timesheet.layer_count)));
+    }
+
+    let mut keyframe_text = String::with_capacity(1024 * layers.len());
+
+    // AE keyframe header (use \r\n for Windows clipboard compatibility)
+    keyframe_text.push_str("Adobe After Effects ");
+    keyframe_text.push_str(version);
+    keyframe_text.push_str(" Keyframe Data\r\n\r\n");
+    keyframe_text.push_str("\tUnits Per Second\t");
+    keyframe_text.push_str(&timesheet.framerate.to_string());
+    keyframe_text.push_str("\r\n\tSource Width\t1000\r\n\tSource Height\t1000\r\n");
+    keyframe_text.push_str("\tSource Pixel Aspect Ratio\t1\r\n\tComp Pixel Aspect Ratio\t1\r\n\r\n");
+
+    for (i, &layer) in layers.iter().enumerate() {
+        if i > 0 {
+            keyframe_text.push_str("\r\n");
+        }
+        push_time_remap_block(&mut keyframe_text, timesheet, layer);
+    }
+
+    keyframe_text.push_str("\r\nEnd of Keyframe Data\r\n");
+
+    Ok(keyframe_text)
+}
+
+/// 写入单个图层的 Time Remap 数据块
+fn push_time_remap_block(keyframe_text: &mut String, timesheet: &TimeSheet, layer: usize) {
+    let framerate = timesheet.framerate as f64;
+
+    // Time Remap effect
+    keyframe_text.push_str("Time Remap\r\n");
+    keyframe_text.push_str("\tFrame\tseconds\t\r\n");
+
+    // Output a keyframe at every value change (12 -> 12A included);
+    // a layer starting blank gets no keyframe until its first drawing
+    for (frame, value) in timesheet.cell_keyframes(layer).filter(|&(frame, value)| frame > 0 || value.is_some()) {
+        // Frame number in timeline
+        keyframe_text.push('\t');
+        keyframe_text.push_str(&frame.to_string());
+        keyframe_text.push('\t');
+
+        match value.and_then(|v| v.number()) {
+            // Time Remap value: convert cell value to seconds
+            // Cell value 1 = frame 0 in source = 0 seconds
+            Some(value) if value > 1 => {
+                // Format with 7 decimal places (AE uses 7), trailing zeros removed
+                let time_seconds = (value - 1) as f64 / framerate;
+                let formatted = format!("{:.7}", time_seconds);
+                keyframe_text.push_str(formatted.trim_end_matches('0').trim_end_matches('.'));
+            }
+            // Empty cell or first drawing - output 0
+            _ => keyframe_text.push('0'),
+        }
+        keyframe_text.push_str("\t\r\n");
+    }
 }
 
-/// 写入 After Effects 关键帧文件
+/// 写入 After Effects 关键帧文件（单个图层的 Time Remap）
 ///
-pub fn write_ae_keyframe_file(_timesheet: &TimeSheet, _path: &str) -> Result<()> {
-    bail!("AE keyframe export not yet implemented for X-Sheet format")
-}
\ No newline at end of file
+pub fn write_ae_keyframe_file(timesheet: &TimeSheet, layer: usize, version: &str, path: &str) -> Result<()> {
+    let keyframe_text = format_ae_keyframes(timesheet, layer, version)?;
+    std::fs::write(path, keyframe_text)
+        .map_err(|e| StsError::io(format!("Failed to write AE keyframe file: {}", path), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_golden() {
+        let mut ts = TimeSheet::new("ae".to_string(), 24, 1, 144);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Number(3)));
+        ts.set_cell(0, 3, None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ae.txt");
+        write_ae_keyframe_file(&ts, 0, "9.0", path.to_str().unwrap()).unwrap();
+
+        let expected = "Adobe After Effects 9.0 Keyframe Data\r\n\r\n\
+            \tUnits Per Second\t24\r\n\
+            \tSource Width\t1000\r\n\
+            \tSource Height\t1000\r\n\
+            \tSource Pixel Aspect Ratio\t1\r\n\
+            \tComp Pixel Aspect Ratio\t1\r\n\r\n\
+            Time Remap\r\n\
+            \tFrame\tseconds\t\r\n\
+            \t0\t0\t\r\n\
+            \t2\t0.0833333\t\r\n\
+            \t3\t0\t\r\n\
+            \r\nEnd of Keyframe Data\r\n";
+        assert_eq!(std::fs::read(&path).unwrap(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_invalid_layer() {
+        let ts = TimeSheet::new("ae".to_string(), 24, 1, 144);
+        assert!(format_ae_keyframes(&ts, 1, "9.0").is_err());
+    }
+
+    #[test]
+    fn test_parse_time_remap_round_trip() {
+        let mut ts = TimeSheet::new("ae".to_string(), 24, 1, 144);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Number(3)));
+        ts.set_cell(0, 3, Some(CellValue::Same));
+        ts.set_cell(0, 4, Some(CellValue::Same));
+        ts.set_cell(0, 5, Some(CellValue::Number(12)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remap.txt");
+        write_ae_keyframe_file(&ts, 0, "9.0", path.to_str().unwrap()).unwrap();
+
+        let parsed = parse_ae_keyframe_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(parsed.framerate, 24);
+        assert_eq!(parsed.layer_count, 1);
+        assert_eq!(parsed.layer_names[0], "Time Remap");
+        // 最后一个关键帧之后全部为 12，只检查到第 6 帧
+        for frame in 0..6 {
+            assert_eq!(parsed.get_actual_value(0, frame), ts.get_actual_value(0, frame));
+        }
+    }
+
+    #[test]
+    fn test_parse_slider_stream() {
+        let content = "Adobe After Effects 8.0 Keyframe Data\n\n\tUnits Per Second\t30\n\n\
+            Effects\tSlider Control #1\tSlider #2\n\tFrame\t\n\t0\t2.4\t\n\t3\t4.6\t\n\n\
+            End of Keyframe Data\n";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slider.txt");
+        std::fs::write(&path, content).unwrap();
+
+        let parsed = parse_ae_keyframe_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(parsed.framerate, 30);
+        assert_eq!(parsed.get_actual_value(0, 2), Some(2));
+        assert_eq!(parsed.get_actual_value(0, 3), Some(5));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.txt");
+        std::fs::write(&path, "hello\n\t0\t1\n").unwrap();
+
+        let err = parse_ae_keyframe_file(path.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Invalid AE keyframe header"));
+    }
+
+    #[test]
+    fn test_multi_layer_blocks() {
+        let mut ts = TimeSheet::new("ae".to_string(), 24, 3, 144);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(1, 0, Some(CellValue::Number(2)));
+        ts.set_cell(2, 0, Some(CellValue::Number(3)));
+
+        let text = format_ae_keyframes_for_layers(&ts, &[0, 1, 2], "9.0").unwrap();
+        assert_eq!(text.matches("Adobe After Effects").count(), 1);
+        assert_eq!(text.matches("Time Remap\r\n").count(), 3);
+        assert!(text.ends_with("End of Keyframe Data\r\n"));
+
+        // 单图层输出与多图层接口一致
+        assert_eq!(
+            format_ae_keyframes(&ts, 1, "9.0").unwrap(),
+            format_ae_keyframes_for_layers(&ts, &[1], "9.0").unwrap()
+        );
+        assert!(format_ae_keyframes_for_layers(&ts, &[0, 3], "9.0").is_err());
+    }
+}
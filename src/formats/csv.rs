@@ -1,60 +1,230 @@
 //! CSV format parser for animation timesheets
 
-use anyhow::{Context, Result};
+use crate::error::{Result, StsError};
 use crate::models::timesheet::{TimeSheet, CellValue};
-use crate::limits::{MAX_LAYERS, MAX_FRAMES};
+use crate::limits::{max_frames, MAX_LAYERS};
 use std::path::Path;
 
+/// Default CSV field delimiter
+pub const DEFAULT_CSV_DELIMITER: u8 = b',';
+
+/// Leading comment line carrying AE comp metadata, e.g. `# source=1920x1080 par=1.0 comp_par=1.0`
+const METADATA_PREFIX: char = '#';
+
+/// Ragged rows reported individually before the rest are summarized
+const MAX_ROW_WARNINGS: usize = 10;
+
+/// Delimiters recognised when sniffing an imported CSV file
+const DELIMITER_CANDIDATES: [u8; 3] = [b',', b';', b'\t'];
+
+/// Detect the delimiter of a CSV file by counting candidates in its first line.
+/// Quoted sections are ignored; falls back to comma when nothing is found.
+pub fn detect_delimiter(content: &str) -> u8 {
+    let first_line = content.lines().next().unwrap_or("");
+
+    let mut counts = [0usize; DELIMITER_CANDIDATES.len()];
+    let mut in_quotes = false;
+    for b in first_line.bytes() {
+        if b == b'"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if let Some(i) = DELIMITER_CANDIDATES.iter().position(|&d| d == b) {
+                counts[i] += 1;
+            }
+        }
+    }
+
+    // Ties keep the earlier candidate, so comma wins by default
+    let best = (1..counts.len()).fold(0, |best, i| if counts[i] > counts[best] { i } else { best });
+    if counts[best] == 0 {
+        DEFAULT_CSV_DELIMITER
+    } else {
+        DELIMITER_CANDIDATES[best]
+    }
+}
+
+/// Source size / pixel aspect ratios stored in the metadata comment line
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CsvMetadata {
+    source_width: u32,
+    source_height: u32,
+    source_pixel_aspect_ratio: f64,
+    comp_pixel_aspect_ratio: f64,
+}
+
+impl CsvMetadata {
+    fn of(timesheet: &TimeSheet) -> Self {
+        Self {
+            source_width: timesheet.source_width,
+            source_height: timesheet.source_height,
+            source_pixel_aspect_ratio: timesheet.source_pixel_aspect_ratio,
+            comp_pixel_aspect_ratio: timesheet.comp_pixel_aspect_ratio,
+        }
+    }
+
+    fn apply(&self, timesheet: &mut TimeSheet) {
+        timesheet.source_width = self.source_width;
+        timesheet.source_height = self.source_height;
+        timesheet.source_pixel_aspect_ratio = self.source_pixel_aspect_ratio;
+        timesheet.comp_pixel_aspect_ratio = self.comp_pixel_aspect_ratio;
+    }
+
+    fn to_line(self) -> String {
+        format!(
+            "{} source={}x{} par={} comp_par={}",
+            METADATA_PREFIX, self.source_width, self.source_height,
+            self.source_pixel_aspect_ratio, self.comp_pixel_aspect_ratio
+        )
+    }
+
+    /// Update fields from a `key=value` comment line; invalid values are ignored
+    ///
+    /// Returns `false` without changing anything if the line is not a metadata
+    /// line, i.e. it is empty or has a token that is not a known `key=value`.
+    fn parse_line(&mut self, line: &str) -> bool {
+        let body = line.trim_start_matches(METADATA_PREFIX);
+        let is_metadata = body.split_whitespace().next().is_some()
+            && body.split_whitespace().all(|token| {
+                matches!(token.split_once('='), Some(("source" | "par" | "comp_par", _)))
+            });
+        if !is_metadata {
+            return false;
+        }
+
+        for (key, value) in body.split_whitespace().filter_map(|token| token.split_once('=')) {
+            match key {
+                "source" => {
+                    if let Some((w, h)) = value.split_once('x') {
+                        if let (Ok(w), Ok(h)) = (w.parse(), h.parse()) {
+                            self.source_width = w;
+                            self.source_height = h;
+                        }
+                    }
+                }
+                "par" => {
+                    if let Some(par) = value.parse::<f64>().ok().filter(|v| *v > 0.0) {
+                        self.source_pixel_aspect_ratio = par;
+                    }
+                }
+                "comp_par" => {
+                    if let Some(par) = value.parse::<f64>().ok().filter(|v| *v > 0.0) {
+                        self.comp_pixel_aspect_ratio = par;
+                    }
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+}
+
+/// Split leading metadata comment lines off the content and read them
+///
+/// Any other line, including other `#` lines, is left in place as data.
+fn split_metadata(content: &str, defaults: CsvMetadata) -> (CsvMetadata, &str) {
+    let mut metadata = defaults;
+    let mut rest = content;
+    while rest.trim_start_matches([' ', '\t']).starts_with(METADATA_PREFIX) {
+        let (line, tail) = rest.split_once('\n').unwrap_or((rest, ""));
+        if !metadata.parse_line(line.trim()) {
+            break;
+        }
+        rest = tail;
+    }
+    (metadata, rest)
+}
+
 /// Try to decode bytes with multiple encodings
-pub(crate) fn decode_with_fallback(bytes: &[u8]) -> Result<String> {
+/// A leading UTF-8 BOM (written by Excel on Windows) is stripped first
+///
+/// Returns the decoded text and the encoding that decoded it cleanly, or `None`
+/// when every candidate failed and the text was forced through UTF-8 with
+/// replacement characters (likely mojibake).
+pub(crate) fn decode_with_fallback(bytes: &[u8]) -> Result<(String, Option<CsvEncoding>)> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+
     // Try encodings in order: UTF-8, GBK (GB2312), Shift-JIS
     let encodings = [
-        encoding_rs::UTF_8,
-        encoding_rs::GBK,
-        encoding_rs::SHIFT_JIS,
+        (encoding_rs::UTF_8, CsvEncoding::Utf8),
+        (encoding_rs::GBK, CsvEncoding::Gb2312),
+        (encoding_rs::SHIFT_JIS, CsvEncoding::ShiftJis),
     ];
 
-    for encoding in &encodings {
+    for (encoding, detected) in encodings {
         let (decoded, _, had_errors) = encoding.decode(bytes);
         if !had_errors {
-            return Ok(decoded.into_owned());
+            return Ok((decoded.into_owned(), Some(detected)));
         }
     }
 
     // If all fail, force decode with UTF-8 (replacing invalid chars)
     let (decoded, _, _) = encoding_rs::UTF_8.decode(bytes);
-    Ok(decoded.into_owned())
+    Ok((decoded.into_owned(), None))
+}
+
+/// Warning reported when [`decode_with_fallback`] had to replace invalid characters
+pub(crate) fn lossy_decode_warning(what: &str) -> String {
+    format!(
+        "{} is not valid UTF-8, GBK or Shift-JIS; decoded as UTF-8 with replacement characters, text may be garbled",
+        what
+    )
 }
 
 /// Parse CSV file and return TimeSheet
 ///
 /// CSV Format:
+/// - Delimiter: auto-detected from the first line (comma, semicolon or tab)
 /// - First row: headers (Frame, layer names...)
 /// - Second row: layer labels (ignored for Frame column, used as layer names)
 /// - Data rows: Frame number in first column, values in subsequent columns
 ///
 /// Value rules:
-/// - Number: Set cell to that number
+/// - Number: Set cell to that number (a single letter suffix such as "12A" is kept)
 /// - Empty string: Hold previous frame's value (including None after ×)
 /// - "×": Set cell to None (empty), and subsequent empty strings continue to hold None
+/// - "-": Set cell to CellValue::Same (explicit hold written by other tools)
+///
+/// A leading `# source=WxH par=.. comp_par=..` comment line sets the source size
+/// and pixel aspect ratios (defaults otherwise); other lines are read as data.
 pub fn parse_csv_file(path: &str) -> Result<TimeSheet> {
+    parse_csv_file_with_warnings(path).map(|result| result.timesheet)
+}
+
+/// Result of parsing a CSV file, with diagnostics for rows that don't match the header
+#[derive(Debug)]
+pub struct CsvParseResult {
+    pub timesheet: TimeSheet,
+    pub warnings: Vec<String>,
+    /// Encoding the file was read as; `None` if it was decoded lossily
+    pub encoding: Option<CsvEncoding>,
+}
+
+/// Parse CSV file like [`parse_csv_file`], also reporting rows whose column count
+/// differs from the header (missing columns are read as holds, extra ones are ignored)
+/// and files that could only be decoded with replacement characters
+pub fn parse_csv_file_with_warnings(path: &str) -> Result<CsvParseResult> {
     // Read raw bytes
     let bytes = std::fs::read(path)
-        .with_context(|| format!("Failed to read CSV file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to read CSV file: {}", path), e))?;
 
     // Decode with multiple encoding support
-    let content = decode_with_fallback(&bytes)
-        .with_context(|| "Failed to decode CSV file")?;
+    let (content, encoding) = decode_with_fallback(&bytes)?;
+    let defaults = CsvMetadata::of(&TimeSheet::new(String::new(), 24, 0, 144));
+    let (metadata, content) = split_metadata(&content, defaults);
 
-    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    // flexible: rows may have fewer columns than the header (e.g. trailing empty layers)
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(detect_delimiter(content))
+        .flexible(true)
+        .from_reader(content.as_bytes());
 
     // Read all records first
     let records: Vec<csv::StringRecord> = reader.records()
-        .collect::<Result<Vec<_>, _>>()
-        .with_context(|| "Failed to parse CSV")?;
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| StsError::Decode(format!("Failed to parse CSV: {}", e)))?;
 
     if records.len() < 2 {
-        anyhow::bail!("CSV file must have at least 2 rows (header + layer names)");
+        return Err(StsError::Decode("CSV file must have at least 2 rows (header + layer names)".to_string()));
     }
 
     // First row is headers, second row contains layer names
@@ -64,17 +234,17 @@ pub fn parse_csv_file(path: &str) -> Result<TimeSheet> {
     // Count layers (exclude first column which is Frame)
     let layer_count = layer_name_row.len().saturating_sub(1);
     if layer_count == 0 {
-        anyhow::bail!("CSV file must have at least one layer column");
+        return Err(StsError::Decode("CSV file must have at least one layer column".to_string()));
     }
 
     // Determine frame count from data rows
     let frame_count = data_rows.len();
 
     if layer_count > MAX_LAYERS {
-        anyhow::bail!("Too many layers in CSV file: {} (max: {})", layer_count, MAX_LAYERS);
+        return Err(StsError::TooManyLayers { found: layer_count, max: MAX_LAYERS });
     }
-    if frame_count > MAX_FRAMES {
-        anyhow::bail!("Too many frames in CSV file: {} (max: {})", frame_count, MAX_FRAMES);
+    if frame_count > max_frames() {
+        return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
     }
 
     // Extract layer names from first row (skip "Frame" column)
@@ -94,6 +264,7 @@ pub fn parse_csv_file(path: &str) -> Result<TimeSheet> {
         144,
     );
     timesheet.ensure_frames(frame_count);
+    metadata.apply(&mut timesheet);
 
     // Set layer names
     for (i, name) in layer_names.iter().enumerate() {
@@ -102,41 +273,66 @@ pub fn parse_csv_file(path: &str) -> Result<TimeSheet> {
         }
     }
 
+    // Rows whose length differs from the layer name row (row numbers are 1-based,
+    // counting the two header rows; comment lines are not counted)
+    let expected_columns = layer_name_row.len();
+    let ragged_rows: Vec<(usize, usize)> = data_rows.iter()
+        .enumerate()
+        .filter(|(_, record)| record.len() != expected_columns)
+        .map(|(i, record)| (i + 3, record.len()))
+        .collect();
+    let mut warnings: Vec<String> = ragged_rows.iter()
+        .take(MAX_ROW_WARNINGS)
+        .map(|(row, columns)| format!("Row {} has {} column(s), expected {}", row, columns, expected_columns))
+        .collect();
+    if ragged_rows.len() > MAX_ROW_WARNINGS {
+        warnings.push(format!(
+            "{} more row(s) with a mismatched column count",
+            ragged_rows.len() - MAX_ROW_WARNINGS
+        ));
+    }
+
     // Parse data rows
     // Track the last value for each layer (for hold logic)
     let mut last_values: Vec<Option<CellValue>> = vec![None; layer_count];
 
     for (frame_idx, record) in data_rows.iter().enumerate() {
         // Process each layer column (skip Frame column at index 0)
-        for layer_idx in 0..layer_count {
+        for (layer_idx, last_value) in last_values.iter_mut().enumerate() {
             let col_idx = layer_idx + 1; // +1 because first column is Frame
             let cell_str = record.get(col_idx).unwrap_or("").trim();
 
             let new_value = if cell_str == "×" {
                 // × means None (empty)
                 None
+            } else if cell_str == "-" {
+                // - means explicit hold (same as above)
+                Some(CellValue::Same)
             } else if cell_str.is_empty() {
                 // Empty string: hold previous value
-                last_values[layer_idx]
+                *last_value
             } else {
-                // Try to parse as number
-                if let Ok(num) = cell_str.parse::<u32>() {
-                    Some(CellValue::Number(num))
-                } else {
-                    // If not a number, treat as hold
-                    last_values[layer_idx]
+                // Try to parse as a drawing number (optionally suffixed, e.g. "12A");
+                // 0 is blank. If not a number, treat as hold
+                match CellValue::parse_drawing(cell_str) {
+                    Some(value) => value.non_blank(),
+                    None => *last_value,
                 }
             };
 
             // Update last value for this layer
-            last_values[layer_idx] = new_value;
+            *last_value = new_value;
 
             // Set cell in timesheet
             timesheet.set_cell(layer_idx, frame_idx, new_value);
         }
     }
 
-    Ok(timesheet)
+    if encoding.is_none() {
+        warnings.insert(0, lossy_decode_warning("File"));
+    }
+
+    Ok(CsvParseResult { timesheet, warnings, encoding })
 }
 
 /// CSV export encoding options
@@ -157,7 +353,7 @@ impl CsvEncoding {
         }
     }
 
-    pub fn from_str(s: &str) -> Self {
+    pub fn from_label(s: &str) -> Self {
         match s {
             "UTF-8" => Self::Utf8,
             "Shift-JIS" => Self::ShiftJis,
@@ -180,38 +376,81 @@ impl CsvEncoding {
     }
 }
 
-/// Write TimeSheet to CSV file with custom header and encoding
-/// Only outputs keyframes (when value changes), uses "×" for transition to empty
+/// CSV export mode options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvExportMode {
+    /// Only output a value when it changes
+    #[default]
+    KeyframesOnly,
+    /// Output the actual value of every frame
+    FullFrames,
+}
+
+impl CsvExportMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::KeyframesOnly => "keyframes",
+            Self::FullFrames => "full",
+        }
+    }
+
+    pub fn from_label(s: &str) -> Self {
+        match s {
+            "full" => Self::FullFrames,
+            _ => Self::KeyframesOnly,
+        }
+    }
+}
+
+/// Write TimeSheet to CSV file with custom header, encoding, delimiter and mode
+/// - KeyframesOnly: only outputs keyframes (when value changes), uses "×" for
+///   transition to empty and "-" for explicit CellValue::Same cells
+/// - FullFrames: outputs the actual value of every frame, empty cells left blank
+///
+/// A `# source=...` metadata comment line is written first only when the source
+/// size or pixel aspect ratios differ from the defaults, so plain sheets stay plain CSV.
 pub fn write_csv_file_with_options(
     timesheet: &TimeSheet,
     path: &str,
     header_name: &str,
     encoding: CsvEncoding,
+    delimiter: u8,
+    mode: CsvExportMode,
 ) -> Result<()> {
     use std::io::Write;
 
+    let delimiter = delimiter as char;
     let mut csv_content = String::new();
 
+    let metadata = CsvMetadata::of(timesheet);
+    if metadata != CsvMetadata::of(&TimeSheet::new(String::new(), 24, 0, 144)) {
+        csv_content.push_str(&metadata.to_line());
+        csv_content.push('\n');
+    }
+
     // First row: Frame, header_name, empty cells...
-    csv_content.push_str("Frame,");
+    csv_content.push_str("Frame");
+    csv_content.push(delimiter);
     csv_content.push_str(header_name);
     for _ in 1..timesheet.layer_count {
-        csv_content.push(',');
+        csv_content.push(delimiter);
     }
     csv_content.push('\n');
 
     // Second row: empty, layer names...
-    csv_content.push(',');
+    csv_content.push(delimiter);
     for (i, layer_name) in timesheet.layer_names.iter().enumerate() {
         csv_content.push_str(layer_name);
         if i < timesheet.layer_count - 1 {
-            csv_content.push(',');
+            csv_content.push(delimiter);
         }
     }
     csv_content.push('\n');
 
-    // Track previous actual values for each layer
-    let mut prev_values: Vec<Option<u32>> = vec![None; timesheet.layer_count];
+    // Keyframe (change point) iterator for each layer; 12 -> 12A is a change
+    let mut layer_keyframes: Vec<_> = (0..timesheet.layer_count)
+        .map(|layer_idx| timesheet.cell_keyframes(layer_idx).peekable())
+        .collect();
 
     // Data rows
     let frame_count = timesheet.total_frames();
@@ -219,25 +458,26 @@ pub fn write_csv_file_with_options(
         // Frame number (1-indexed)
         csv_content.push_str(&(frame_idx + 1).to_string());
 
-        for layer_idx in 0..timesheet.layer_count {
-            csv_content.push(',');
+        for (layer_idx, keyframes) in layer_keyframes.iter_mut().enumerate() {
+            csv_content.push(delimiter);
 
-            // Get the actual value for this cell
-            let current_value = timesheet.get_actual_value(layer_idx, frame_idx);
-            let prev_value = prev_values[layer_idx];
+            let keyframe = keyframes.next_if(|&(frame, _)| frame == frame_idx);
 
-            if current_value != prev_value {
+            if mode == CsvExportMode::FullFrames {
+                if let Some(value) = timesheet.get_actual_cell(layer_idx, frame_idx) {
+                    csv_content.push_str(&value.to_string());
+                }
+            } else if timesheet.get_cell(layer_idx, frame_idx) == Some(&CellValue::Same) {
+                // Explicit hold - keep it distinguishable from an implicit one
+                csv_content.push('-');
+            } else if let Some((frame, value)) = keyframe {
                 // Value changed - output it
-                match current_value {
-                    Some(n) => csv_content.push_str(&n.to_string()),
-                    None => {
-                        // Changed from having a value to no value - output ×
-                        if prev_value.is_some() {
-                            csv_content.push('×');
-                        }
-                    }
+                match value {
+                    Some(value) => csv_content.push_str(&value.to_string()),
+                    // Changed from having a value to no value - output ×
+                    None if frame > 0 => csv_content.push('×'),
+                    None => {}
                 }
-                prev_values[layer_idx] = current_value;
             }
             // If value is the same as previous, output nothing (empty)
         }
@@ -247,14 +487,216 @@ pub fn write_csv_file_with_options(
     // Encode and write to file
     let encoded_bytes = encoding.encode(&csv_content);
     let mut file = std::fs::File::create(path)
-        .with_context(|| format!("Failed to create CSV file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to create CSV file: {}", path), e))?;
     file.write_all(&encoded_bytes)
-        .with_context(|| "Failed to write CSV file")?;
+        .map_err(|e| StsError::io(format!("Failed to write CSV file: {}", path), e))?;
 
     Ok(())
 }
 
 /// Write TimeSheet to CSV file (legacy function for compatibility)
 pub fn write_csv_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
-    write_csv_file_with_options(timesheet, path, "动画", CsvEncoding::Gb2312)
+    write_csv_file_with_options(timesheet, path, "动画", CsvEncoding::Gb2312, DEFAULT_CSV_DELIMITER, CsvExportMode::KeyframesOnly)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("Frame,A,B\n1,1,2"), b',');
+        assert_eq!(detect_delimiter("Frame;A;B\n1;1;2"), b';');
+        assert_eq!(detect_delimiter("Frame\tA\tB"), b'\t');
+        assert_eq!(detect_delimiter("\"a;b;c\",x,y"), b',');
+        assert_eq!(detect_delimiter("Frame"), b',');
+    }
+
+    #[test]
+    fn test_semicolon_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("semi.csv");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("semi".to_string(), 24, 2, 144);
+        ts.ensure_frames(3);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(1, 1, Some(CellValue::Number(2)));
+
+        write_csv_file_with_options(&ts, path, "动画", CsvEncoding::Utf8, b';', CsvExportMode::KeyframesOnly).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("Frame;动画;\n"));
+
+        let loaded = parse_csv_file(path).unwrap();
+        assert_eq!(loaded.layer_count, 2);
+        assert_eq!(loaded.total_frames(), 3);
+        for layer in 0..2 {
+            for frame in 0..3 {
+                assert_eq!(loaded.get_actual_value(layer, frame), ts.get_actual_value(layer, frame));
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("same.csv");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("same".to_string(), 24, 1, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(0, 0, Some(CellValue::Number(3)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Same));
+
+        write_csv_file_with_options(&ts, path, "动画", CsvEncoding::Utf8, b',', CsvExportMode::KeyframesOnly).unwrap();
+        let loaded = parse_csv_file(path).unwrap();
+
+        for frame in 0..4 {
+            assert_eq!(loaded.get_cell(0, frame), ts.get_cell(0, frame));
+        }
+        assert_eq!(loaded.get_actual_value(0, 2), Some(3));
+    }
+
+    #[test]
+    fn test_full_frames_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("full.csv");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("full".to_string(), 24, 2, 144);
+        ts.ensure_frames(3);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Number(2)));
+        ts.set_cell(1, 1, Some(CellValue::Number(5)));
+
+        write_csv_file_with_options(&ts, path, "动画", CsvEncoding::Utf8, b',', CsvExportMode::FullFrames).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+
+        assert_eq!(lines, vec!["Frame,动画,", ",A,B", "1,1,", "2,1,5", "3,2,"]);
+    }
+
+    #[test]
+    fn test_bom_and_ragged_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("excel.csv");
+
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend_from_slice("Frame,动画,\n,A,\"B\nline2\"\n1,1,2\n2\n3,×,3\n".as_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        let loaded = parse_csv_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.layer_count, 2);
+        assert_eq!(loaded.layer_names, vec!["A", "B\nline2"]);
+        assert_eq!(loaded.total_frames(), 3);
+        assert_eq!(loaded.get_actual_value(0, 1), Some(1));
+        assert_eq!(loaded.get_actual_value(0, 2), None);
+        assert_eq!(loaded.get_actual_value(1, 2), Some(3));
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meta.csv");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("meta".to_string(), 24, 1, 144);
+        ts.ensure_frames(2);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.source_width = 1920;
+        ts.source_height = 1080;
+        ts.source_pixel_aspect_ratio = 0.9;
+
+        write_csv_file_with_options(&ts, path, "动画", CsvEncoding::Utf8, b';', CsvExportMode::KeyframesOnly).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("# source=1920x1080 par=0.9 comp_par=1\nFrame;动画\n"));
+
+        let loaded = parse_csv_file(path).unwrap();
+        assert_eq!((loaded.source_width, loaded.source_height), (1920, 1080));
+        assert_eq!(loaded.source_pixel_aspect_ratio, 0.9);
+        assert_eq!(loaded.comp_pixel_aspect_ratio, 1.0);
+        assert_eq!(loaded.get_actual_value(0, 0), Some(1));
+        assert_eq!(loaded.get_actual_value(0, 1), None);
+
+        // 没有元数据行时保持默认值
+        std::fs::write(path, "Frame,动画\n,A\n1,1\n").unwrap();
+        let loaded = parse_csv_file(path).unwrap();
+        assert_eq!((loaded.source_width, loaded.source_height), (640, 480));
+
+        // 其他 # 开头的行不是元数据，保留为表头
+        std::fs::write(path, "# source=800x600\n#,动画\n,A\n1,1\n").unwrap();
+        let loaded = parse_csv_file(path).unwrap();
+        assert_eq!((loaded.source_width, loaded.source_height), (800, 600));
+        assert_eq!(loaded.layer_names[0], "A");
+        assert_eq!(loaded.get_actual_value(0, 0), Some(1));
+    }
+
+    #[test]
+    fn test_ragged_row_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ragged.csv");
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "Frame,A,B\n,A,B\n1,1,2\n2,3\n3,4,5,6\n4,,\n").unwrap();
+        let result = parse_csv_file_with_warnings(path).unwrap();
+        assert_eq!(result.warnings, vec![
+            "Row 4 has 2 column(s), expected 3".to_string(),
+            "Row 5 has 4 column(s), expected 3".to_string(),
+        ]);
+        // 缺少的列按延续处理
+        assert_eq!(result.timesheet.get_actual_value(1, 1), Some(2));
+
+        // 正常导出的文件没有警告
+        let mut ts = TimeSheet::new("ok".to_string(), 24, 3, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(2, 1, Some(CellValue::Number(1)));
+        write_csv_file(&ts, path).unwrap();
+        assert!(parse_csv_file_with_warnings(path).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_decode_fallback_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("encoded.csv");
+        let path = path.to_str().unwrap();
+
+        let (gbk, _, _) = encoding_rs::GBK.encode("Frame,原画\n,原画\n1,1\n");
+        std::fs::write(path, &gbk).unwrap();
+        let result = parse_csv_file_with_warnings(path).unwrap();
+        assert_eq!(result.encoding, Some(CsvEncoding::Gb2312));
+        assert_eq!(result.timesheet.layer_names, vec!["原画"]);
+        assert!(result.warnings.is_empty());
+
+        // 0x81 0x7F 在三种编码中都无效，只能带替换字符解码
+        std::fs::write(path, b"Frame,A\n,\x81\x7F\n1,1\n").unwrap();
+        let result = parse_csv_file_with_warnings(path).unwrap();
+        assert_eq!(result.encoding, None);
+        assert_eq!(result.warnings, vec![lossy_decode_warning("File")]);
+        assert!(result.timesheet.layer_names[0].contains('\u{FFFD}'));
+    }
+
+    #[test]
+    fn test_suffix_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("suffix.csv");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("suffix".to_string(), 24, 1, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(0, 0, Some(CellValue::Number(12)));
+        ts.set_cell(0, 1, Some(CellValue::Labeled(12, 'A')));
+        ts.set_cell(0, 2, Some(CellValue::Labeled(12, 'A')));
+        ts.set_cell(0, 3, Some(CellValue::Number(13)));
+
+        write_csv_file_with_options(&ts, path, "动画", CsvEncoding::Utf8, b',', CsvExportMode::KeyframesOnly).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.ends_with("1,12\n2,12A\n3,\n4,13\n"), "{}", content);
+
+        let loaded = parse_csv_file(path).unwrap();
+        for frame in 0..4 {
+            assert_eq!(loaded.get_actual_cell(0, frame), ts.get_actual_cell(0, frame));
+        }
+    }
 }
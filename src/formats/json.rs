@@ -0,0 +1,14 @@
+//! JSON format (lossless interchange via serde)
+
+use crate::error::Result;
+use crate::models::timesheet::TimeSheet;
+
+/// Parse a timesheet saved as JSON
+pub fn parse_json_file(path: &str) -> Result<TimeSheet> {
+    TimeSheet::load_json(path)
+}
+
+/// Write a timesheet as human-readable JSON
+pub fn write_json_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
+    timesheet.save_json(path)
+}
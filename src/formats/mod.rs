@@ -1,23 +1,35 @@
 pub mod ae_keyframe;
+pub mod ae_jsx;
 pub mod sts;
 pub mod tdts;
 pub mod xdts;
 pub mod csv;
 pub mod sxf;
+pub mod json;
+pub mod srt;
 
-pub use ae_keyframe::{parse_ae_keyframe_file, write_ae_keyframe_file};
-pub use sts::{parse_sts_file, write_sts_file};
-pub use tdts::{parse_tdts_file, TdtsParseResult};
-pub use xdts::parse_xdts_file;
-pub use csv::{parse_csv_file, write_csv_file, write_csv_file_with_options, CsvEncoding};
+pub use ae_keyframe::{parse_ae_keyframe_file, write_ae_keyframe_file, format_ae_keyframes, format_ae_keyframes_for_layers};
+pub use ae_jsx::{format_ae_jsx, write_ae_jsx};
+pub use sts::{parse_sts_file, write_sts_file, sts_layer_name_warning, sts_suffix_warning, STS_MAX_FRAMES, STS_MAX_NAME_BYTES};
+pub use tdts::{parse_tdts_file, write_tdts_file, TdtsParseResult};
+pub use xdts::{parse_xdts_file, parse_xdts_file_with_report, write_xdts_file, XdtsParseResult};
+pub use csv::{
+    parse_csv_file, parse_csv_file_with_warnings, write_csv_file, write_csv_file_with_options, CsvParseResult,
+    detect_delimiter, CsvEncoding, CsvExportMode, DEFAULT_CSV_DELIMITER,
+};
+pub use json::{parse_json_file, write_json_file};
+pub use srt::{format_srt, write_srt_file};
 pub use sxf::{
     parse_sxf_file,
     parse_sxf_binary,
     parse_sxf_groups,
+    parse_sxf_groups_with_warnings,
+    write_sxf_binary,
     write_groups_to_csv,
     groups_to_timesheet,
     LayerGroup,
     LayerData,
+    SxfParseResult,
 };
 
 use crate::models::timesheet::{TimeSheet, CellValue};
@@ -40,3 +52,40 @@ pub fn fill_keyframes(
         }
     }
 }
+
+/// Marker for an empty cell in XDTS/TDTS tracks
+pub(crate) const SYMBOL_NULL_CELL: &str = "SYMBOL_NULL_CELL";
+
+/// Map an XDTS/TDTS track symbol to a cell value
+///
+/// `SYMBOL_NULL_CELL` is a blank (`Some(None)`); tick and hyphen marks are a
+/// deliberately held exposure and become `CellValue::Same`. Values that are
+/// not symbols, such as drawing numbers, return `None`.
+pub(crate) fn parse_track_symbol(value: &str) -> Option<Option<CellValue>> {
+    match value {
+        SYMBOL_NULL_CELL => Some(None),
+        "SYMBOL_TICK_1" | "SYMBOL_TICK_2" | "SYMBOL_HYPHEN" => Some(Some(CellValue::Same)),
+        _ => None,
+    }
+}
+
+/// Build the XDTS/TDTS cell tracks of a timesheet as (track_no, [(frame, value)])
+///
+/// Only keyframes (value changes) are listed: suffixed drawings such as "12A"
+/// are written as-is and a change to an empty cell is `SYMBOL_NULL_CELL`.
+/// Layers that never show a drawing are left out.
+pub(crate) fn keyframe_tracks(timesheet: &TimeSheet) -> Vec<(usize, Vec<(usize, String)>)> {
+    (0..timesheet.layer_count)
+        .filter_map(|layer_idx| {
+            let frames: Vec<(usize, String)> = timesheet.cell_keyframes(layer_idx)
+                // A leading blank needs no keyframe
+                .filter(|&(frame, value)| frame > 0 || value.is_some())
+                .map(|(frame, value)| {
+                    let value = value.map_or_else(|| SYMBOL_NULL_CELL.to_string(), |v| v.to_string());
+                    (frame, value)
+                })
+                .collect();
+            (!frames.is_empty()).then_some((layer_idx, frames))
+        })
+        .collect()
+}
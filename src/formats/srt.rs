@@ -0,0 +1,107 @@
+//! SRT subtitle writer
+//!
+//! 将指定列（台词列）的每个变化点写成一条字幕：文本为该格的值，
+//! 持续到下一个变化点；空格结束当前字幕。
+
+use std::fmt::Write as _;
+
+use crate::error::{Result, StsError};
+use crate::models::timesheet::TimeSheet;
+
+/// Convert a frame index to an SRT timecode (`HH:MM:SS,mmm`)
+fn frame_to_srt_time(frame: usize, framerate: u32) -> String {
+    let total_ms = frame as u64 * 1000 / framerate as u64;
+    let ms = total_ms % 1000;
+    let total_secs = total_ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60,
+        ms
+    )
+}
+
+/// Format one layer of a TimeSheet as SRT text
+pub fn format_srt(timesheet: &TimeSheet, dialogue_layer: usize) -> Result<String> {
+    if dialogue_layer >= timesheet.layer_count {
+        return Err(StsError::InvalidData(format!(
+            "Layer {} does not exist (layer count: {})",
+            dialogue_layer, timesheet.layer_count
+        )));
+    }
+    if timesheet.framerate == 0 {
+        return Err(StsError::InvalidData("Framerate must be greater than 0".to_string()));
+    }
+
+    let total_frames = timesheet.total_frames();
+    let keyframes: Vec<_> = timesheet.cell_keyframes(dialogue_layer).collect();
+
+    let mut output = String::new();
+    let mut cue_index = 1;
+    for (i, &(start, value)) in keyframes.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+        let end = keyframes.get(i + 1).map(|(f, _)| *f).unwrap_or(total_frames);
+
+        let _ = writeln!(output, "{}", cue_index);
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            frame_to_srt_time(start, timesheet.framerate),
+            frame_to_srt_time(end, timesheet.framerate)
+        );
+        let _ = writeln!(output, "{}", value);
+        output.push('\n');
+        cue_index += 1;
+    }
+
+    Ok(output)
+}
+
+/// Write one layer of a TimeSheet to an SRT subtitle file
+pub fn write_srt_file(timesheet: &TimeSheet, dialogue_layer: usize, path: &str) -> Result<()> {
+    let content = format_srt(timesheet, dialogue_layer)?;
+    std::fs::write(path, content)
+        .map_err(|e| StsError::io(format!("Failed to write SRT file: {}", path), e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timesheet::CellValue;
+
+    #[test]
+    fn test_frame_to_srt_time() {
+        assert_eq!(frame_to_srt_time(0, 24), "00:00:00,000");
+        assert_eq!(frame_to_srt_time(36, 24), "00:00:01,500");
+        assert_eq!(frame_to_srt_time(24 * 3661, 24), "01:01:01,000");
+    }
+
+    #[test]
+    fn test_format_srt() {
+        let mut ts = TimeSheet::new("dialogue".to_string(), 24, 2, 144);
+        ts.ensure_frames(96);
+        // 0-23: 1, 24-35: 2, 36-47: 空, 48-95: 3
+        for (start, end, n) in [(0, 24, 1), (24, 36, 2), (48, 96, 3)] {
+            ts.set_cell(1, start, Some(CellValue::Number(n)));
+            for frame in start + 1..end {
+                ts.set_cell(1, frame, Some(CellValue::Same));
+            }
+        }
+
+        let srt = format_srt(&ts, 1).unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,000\n1\n\n\
+             2\n00:00:01,000 --> 00:00:01,500\n2\n\n\
+             3\n00:00:02,000 --> 00:00:04,000\n3\n\n"
+        );
+
+        // 空列不产生字幕
+        assert_eq!(format_srt(&ts, 0).unwrap(), "");
+        assert!(matches!(format_srt(&ts, 2), Err(StsError::InvalidData(_))));
+    }
+}
@@ -1,44 +1,113 @@
-use anyhow::{Result, bail, Context};
+use crate::error::{Result, StsError};
 use crate::models::TimeSheet;
-use crate::models::timesheet::CellValue;
+use crate::limits::max_frames;
+use crate::models::timesheet::{CellValue, LayerCells};
 use encoding_rs::SHIFT_JIS;
 use std::fs::File;
 use std::io::{Read, Write};
 
+/// 扩展块标识（位于层名称区之后，旧版本读取器会忽略）
+const EXTENSION_TAG: &[u8; 4] = b"STSX";
+
+/// 没有扩展块的旧文件的格式版本
+const STS_VERSION_LEGACY: u8 = 1;
+
+/// 当前写入的格式版本（记录在扩展块中）
+const STS_VERSION: u8 = 2;
+
+/// 帧数据中表示 "-"（CellValue::Same）的值
+const SAME_SENTINEL: u16 = 0xFFFF;
+
+/// 单元格为 u16 且 0xFFFF 表示 "-"，可保存的最大数字
+const STS_MAX_CELL_VALUE: u32 = SAME_SENTINEL as u32 - 1;
+
+/// STS 帧数字段为 u16，可保存的最大帧数
+pub const STS_MAX_FRAMES: usize = 65535;
+
+/// STS 图层名长度字段为 u8，Shift-JIS 编码后可保存的最大字节数
+pub const STS_MAX_NAME_BYTES: usize = 255;
+
+/// 检查图层名能否无损保存到 STS，不能时返回说明
+/// （含 Shift-JIS 无法编码的字符，或编码后超过 255 字节会被截断）
+pub fn sts_layer_name_warning(name: &str) -> Option<String> {
+    let (name_bytes, _, had_errors) = SHIFT_JIS.encode(name);
+    if had_errors {
+        Some(format!("Layer name '{}' contains characters that cannot be saved in STS (Shift-JIS)", name))
+    } else if name_bytes.len() > STS_MAX_NAME_BYTES {
+        Some(format!(
+            "Layer name '{}' is {} bytes in Shift-JIS and will be truncated to {} when saved as STS",
+            name, name_bytes.len(), STS_MAX_NAME_BYTES
+        ))
+    } else {
+        None
+    }
+}
+
+/// 检查表中是否有带字母后缀的编号（如 "12A"），STS 只能保存数字部分，有时返回说明
+pub fn sts_suffix_warning(timesheet: &TimeSheet) -> Option<String> {
+    let mut count = 0;
+    let mut first = None;
+    for layer in 0..timesheet.layer_count {
+        for frame in 0..timesheet.total_frames() {
+            if let Some(value @ CellValue::Labeled(..)) = timesheet.get_cell(layer, frame) {
+                count += 1;
+                first.get_or_insert((layer, frame, *value));
+            }
+        }
+    }
+    let (layer, frame, value) = first?;
+    Some(format!(
+        "{} cell(s) have letter suffixes that STS cannot store and were saved as plain numbers (first: {} at layer '{}' frame {})",
+        count, value, timesheet.layer_names[layer], frame + 1
+    ))
+}
+
 /// 解析 STS 文件
 ///
 /// STS 文件格式：
-/// 1. 文件头（23字节）
-/// 2. 帧数据区（layer_count × frame_count × 2字节）
+/// 1. 文件头（23字节，最后 2 字节为帧率，旧文件为 0 时按 24fps 处理）
+/// 2. 帧数据区（layer_count × frame_count × 2字节，0 = 空，0xFFFF = "-"）
 /// 3. 层名称区（每层：1字节长度 + N字节Shift-JIS名称）
+/// 4. 扩展块（可选：4字节 "STSX" + 1字节格式版本 + 版本数据）
+///    - 版本 1：无扩展块（旧文件）
+///    - 版本 2：2字节每页帧数
 pub fn parse_sts_file(path: &str) -> Result<TimeSheet> {
     let mut file = File::open(path)
-        .with_context(|| format!("Unable to open: {}", path))?;
+        .map_err(|e| StsError::io(format!("Unable to open: {}", path), e))?;
 
     let mut buffer = Vec::new();
     file.read_to_end(&mut buffer)
-        .with_context(|| format!("Unable to read: {}", path))?;
+        .map_err(|e| StsError::io(format!("Unable to read: {}", path), e))?;
 
     if buffer.len() < 23 {
-        bail!("Invalid STS file: too small");
+        return Err(StsError::Decode("Invalid STS file: too small".to_string()));
     }
 
     // 解析文件头
     if buffer[0] != 0x11 {
-        bail!("Invalid STS file: invalid signature");
+        return Err(StsError::InvalidSignature("Invalid STS file: invalid signature".to_string()));
     }
 
     let header_str = std::str::from_utf8(&buffer[1..18])
-        .context("Invalid STS file: invalid header")?;
+        .map_err(|_| StsError::InvalidSignature("Invalid STS file: invalid header".to_string()))?;
     if header_str != "ShiraheiTimeSheet" {
-        bail!("Invalid STS file: invalid header");
+        return Err(StsError::InvalidSignature("Invalid STS file: invalid header".to_string()));
     }
 
     let layer_count = buffer[18] as usize;
     let frame_count = u16::from_le_bytes([buffer[19], buffer[20]]) as usize;
 
+    // 帧率 (旧文件此处为填充 0x00 0x00，默认 24fps)
+    let framerate = match u16::from_le_bytes([buffer[21], buffer[22]]) {
+        0 => 24,
+        fps => fps as u32,
+    };
+
     if layer_count == 0 || frame_count == 0 {
-        bail!("Invalid STS file: invalid layer count or frame count: {} layers, {} frames", layer_count, frame_count);
+        return Err(StsError::Decode(format!("Invalid STS file: invalid layer count or frame count: {} layers, {} frames", layer_count, frame_count)));
+    }
+    if frame_count > max_frames() {
+        return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
     }
 
     // 计算帧数据区大小
@@ -46,20 +115,23 @@ pub fn parse_sts_file(path: &str) -> Result<TimeSheet> {
     let frame_data_end = 23 + frame_data_size;
 
     if buffer.len() < frame_data_end {
-        bail!("Invalid STS file: incomplete frame data");
+        return Err(StsError::Decode("Invalid STS file: incomplete frame data".to_string()));
     }
 
     // 解析帧数据
-    let mut cells = vec![vec![None; frame_count]; layer_count];
+    let mut cells = vec![LayerCells::new(frame_count); layer_count];
 
-    for layer in 0..layer_count {
+    for (layer, layer_cells) in cells.iter_mut().enumerate() {
         for frame in 0..frame_count {
             let offset = 23 + (layer * frame_count + frame) * 2;
             let cell_value = u16::from_le_bytes([buffer[offset], buffer[offset + 1]]);
 
-            if cell_value > 0 {
-                cells[layer][frame] = Some(CellValue::Number(cell_value as u32));
-            }
+            let cell = match cell_value {
+                0 => None,
+                SAME_SENTINEL => Some(CellValue::Same),
+                n => Some(CellValue::Number(n as u32)),
+            };
+            layer_cells.set(frame, cell);
         }
     }
 
@@ -94,6 +166,25 @@ pub fn parse_sts_file(path: &str) -> Result<TimeSheet> {
         layer_names.push(format!("Layer{}", layer_names.len() + 1));
     }
 
+    // 检测格式版本（旧文件没有扩展块）
+    let has_extension = buffer.len() > pos + 4 && &buffer[pos..pos + 4] == EXTENSION_TAG;
+    let version = if has_extension { buffer[pos + 4] } else { STS_VERSION_LEGACY };
+
+    let frames_per_page = match version {
+        STS_VERSION_LEGACY => 144,  // 默认每页144帧
+        STS_VERSION => {
+            let data = pos + 5;
+            if buffer.len() < data + 2 {
+                return Err(StsError::Decode("Invalid STS file: incomplete extension block".to_string()));
+            }
+            match u16::from_le_bytes([buffer[data], buffer[data + 1]]) {
+                0 => 144,
+                fpp => fpp as u32,
+            }
+        }
+        v => return Err(StsError::UnsupportedVersion { format: "STS", version: v as u32 }),
+    };
+
     // 提取文件名作为sheet名称
     let sheet_name = std::path::Path::new(path)
         .file_stem()
@@ -103,8 +194,8 @@ pub fn parse_sts_file(path: &str) -> Result<TimeSheet> {
 
     Ok(TimeSheet {
         name: sheet_name,
-        framerate: 24,  // 默认24fps
-        frames_per_page: 144,  // 默认每页144帧
+        framerate,
+        frames_per_page,
         layer_count,
         layer_names,
         cells,
@@ -122,15 +213,37 @@ pub fn write_sts_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
     let frame_count = timesheet.total_frames();
 
     if layer_count > 255 {
-        bail!("Too many layers: {}, maximum is 255", layer_count);
+        return Err(StsError::TooManyLayers { found: layer_count, max: 255 });
+    }
+
+    if frame_count > STS_MAX_FRAMES {
+        return Err(StsError::TooManyFrames { found: frame_count, max: STS_MAX_FRAMES });
     }
 
-    if frame_count > 65535 {
-        bail!("Too many frames: {}, maximum is 65535", frame_count);
+    if timesheet.framerate > 65535 {
+        return Err(StsError::InvalidData(format!("Invalid framerate: {}, maximum is 65535", timesheet.framerate)));
+    }
+
+    if timesheet.frames_per_page > 65535 {
+        return Err(StsError::InvalidData(format!("Invalid frames per page: {}, maximum is 65535", timesheet.frames_per_page)));
+    }
+
+    // 超出 u16 的数字会被截断，在创建文件前报错
+    for layer in 0..layer_count {
+        for frame in 0..frame_count {
+            if let Some(n) = timesheet.get_cell(layer, frame).and_then(CellValue::number) {
+                if n > STS_MAX_CELL_VALUE {
+                    return Err(StsError::InvalidData(format!(
+                        "Layer \"{}\" frame {}: value {} exceeds the STS maximum of {}",
+                        timesheet.layer_names[layer], frame + 1, n, STS_MAX_CELL_VALUE
+                    )));
+                }
+            }
+        }
     }
 
     let mut file = File::create(path)
-        .with_context(|| format!("Unable to create: {}", path))?;
+        .map_err(|e| StsError::io(format!("Unable to create: {}", path), e))?;
 
     // === 文件头 (23 bytes) ===
 
@@ -146,14 +259,16 @@ pub fn write_sts_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
     // 帧数 (2 bytes, little-endian)
     file.write_all(&(frame_count as u16).to_le_bytes())?;
 
-    // 填充 (2 bytes)
-    file.write_all(&[0x00, 0x00])?;
+    // 帧率 (2 bytes, little-endian)
+    file.write_all(&(timesheet.framerate as u16).to_le_bytes())?;
 
     // === 帧数据区 (layer_count × frame_count × 2 bytes) ===
+    // 带后缀的编号只保存数字部分（见 sts_suffix_warning）
     for layer in 0..layer_count {
         for frame in 0..frame_count {
-            let cell_value = match timesheet.get_actual_value(layer, frame) {
-                Some(n) => n as u16,
+            let cell_value = match timesheet.get_cell(layer, frame) {
+                Some(CellValue::Number(n) | CellValue::Labeled(n, _)) => *n as u16,
+                Some(CellValue::Same) => SAME_SENTINEL,
                 None => 0u16,
             };
             file.write_all(&cell_value.to_le_bytes())?;
@@ -171,9 +286,9 @@ pub fn write_sts_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
             eprintln!("Warning: Layer name '{}' contains character that cannot encode to Shift-JIS", name);
         }
 
-        let name_bytes = if name_bytes.len() > 255 {
+        let name_bytes = if name_bytes.len() > STS_MAX_NAME_BYTES {
             eprintln!("Warning: Layer name over 255 characters will be truncated: '{}'", name);
-            &name_bytes[..255]
+            &name_bytes[..STS_MAX_NAME_BYTES]
         } else {
             &name_bytes
         };
@@ -183,5 +298,182 @@ pub fn write_sts_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
         file.write_all(name_bytes)?;
     }
 
+    // === 扩展块 ===
+    file.write_all(EXTENSION_TAG)?;
+    file.write_all(&[STS_VERSION])?;
+    file.write_all(&(timesheet.frames_per_page as u16).to_le_bytes())?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framerate_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fps.sts");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("fps".to_string(), 30, 2, 144);
+        ts.ensure_frames(10);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+
+        write_sts_file(&ts, path).unwrap();
+        let loaded = parse_sts_file(path).unwrap();
+
+        assert_eq!(loaded.framerate, 30);
+        assert_eq!(loaded.get_cell(0, 0), Some(&CellValue::Number(1)));
+    }
+
+    #[test]
+    fn test_value_too_large() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.sts");
+
+        let mut ts = TimeSheet::new("big".to_string(), 24, 2, 144);
+        ts.ensure_frames(10);
+        ts.set_cell(1, 4, Some(CellValue::Number(70000)));
+
+        let err = write_sts_file(&ts, path.to_str().unwrap()).unwrap_err();
+        match err {
+            StsError::InvalidData(message) => {
+                assert!(message.contains("\"B\" frame 5"), "{}", message);
+                assert!(message.contains("70000"), "{}", message);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        // 不留下损坏的文件
+        assert!(!path.exists());
+
+        ts.set_cell(1, 4, Some(CellValue::Number(STS_MAX_CELL_VALUE)));
+        write_sts_file(&ts, path.to_str().unwrap()).unwrap();
+        let loaded = parse_sts_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.get_cell(1, 4), Some(&CellValue::Number(STS_MAX_CELL_VALUE)));
+    }
+
+    #[test]
+    fn test_suffix_saved_as_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("suffix.sts");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("suffix".to_string(), 24, 1, 144);
+        ts.ensure_frames(3);
+        ts.set_cell(0, 0, Some(CellValue::Number(3)));
+        assert_eq!(sts_suffix_warning(&ts), None);
+
+        ts.set_cell(0, 1, Some(CellValue::Labeled(3, 'B')));
+        let warning = sts_suffix_warning(&ts).unwrap();
+        assert!(warning.contains("3B at layer 'A' frame 2"), "{}", warning);
+
+        write_sts_file(&ts, path).unwrap();
+        let loaded = parse_sts_file(path).unwrap();
+        assert_eq!(loaded.get_cell(0, 1), Some(&CellValue::Number(3)));
+    }
+
+    #[test]
+    fn test_legacy_padding_defaults_to_24fps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.sts");
+
+        // 旧格式：1 层 1 帧，填充字节为 0x00 0x00
+        let mut bytes = vec![0x11];
+        bytes.extend_from_slice(b"ShiraheiTimeSheet");
+        bytes.push(1);
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        bytes.extend_from_slice(&5u16.to_le_bytes());
+        bytes.push(1);
+        bytes.push(b'A');
+        std::fs::write(&path, bytes).unwrap();
+
+        let loaded = parse_sts_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.framerate, 24);
+        assert_eq!(loaded.get_cell(0, 0), Some(&CellValue::Number(5)));
+        assert_eq!(loaded.layer_names[0], "A");
+        assert_eq!(loaded.frames_per_page, 144);
+    }
+
+    #[test]
+    fn test_frames_per_page_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fpp.sts");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("fpp".to_string(), 24, 3, 72);
+        ts.ensure_frames(100);
+
+        write_sts_file(&ts, path).unwrap();
+        let loaded = parse_sts_file(path).unwrap();
+
+        assert_eq!(loaded.frames_per_page, 72);
+        assert_eq!(loaded.layer_names, ts.layer_names);
+        assert_eq!(loaded.get_page_and_frame(72), (2, 1));
+    }
+
+    #[test]
+    fn test_same_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("same.sts");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("same".to_string(), 24, 1, 144);
+        ts.ensure_frames(6);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 2, Some(CellValue::Number(2)));
+        ts.set_cell(0, 3, Some(CellValue::Same));
+
+        write_sts_file(&ts, path).unwrap();
+        let loaded = parse_sts_file(path).unwrap();
+
+        assert_eq!(loaded.get_cell(0, 1), None);
+        assert_eq!(loaded.get_cell(0, 2), Some(&CellValue::Number(2)));
+        assert_eq!(loaded.get_cell(0, 3), Some(&CellValue::Same));
+        assert_eq!(loaded.get_actual_value(0, 3), Some(2));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("future.sts");
+        let path = path.to_str().unwrap();
+
+        let mut ts = TimeSheet::new("future".to_string(), 24, 1, 144);
+        ts.ensure_frames(1);
+        write_sts_file(&ts, path).unwrap();
+
+        // 将扩展块中的版本号改为未知版本
+        let mut bytes = std::fs::read(path).unwrap();
+        let version_pos = bytes.len() - 3;
+        bytes[version_pos] = 99;
+        std::fs::write(path, bytes).unwrap();
+
+        let err = parse_sts_file(path).unwrap_err();
+        assert!(matches!(err, StsError::UnsupportedVersion { format: "STS", version: 99 }));
+        assert_eq!(err.to_string(), "Unsupported STS version 99");
+    }
+
+    #[test]
+    fn test_typed_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.sts");
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, [0u8; 4]).unwrap();
+        assert!(matches!(parse_sts_file(path), Err(StsError::Decode(_))));
+
+        let missing = dir.path().join("missing.sts");
+        assert!(matches!(parse_sts_file(missing.to_str().unwrap()), Err(StsError::Io { .. })));
+
+        let ts = TimeSheet::new("wide".to_string(), 24, 256, 144);
+        let err = write_sts_file(&ts, path).unwrap_err();
+        assert!(matches!(err, StsError::TooManyLayers { found: 256, max: 255 }));
+
+        // 转换为 anyhow::Error 后消息不变
+        let message = err.to_string();
+        let err: anyhow::Error = err.into();
+        assert_eq!(err.to_string(), message);
+    }
+}
@@ -1,21 +1,22 @@
 // SXF (摄影表) format parser - unified text and binary support
 
-use anyhow::{Context, Result, bail};
+use crate::error::{Result, StsError};
 use crate::models::timesheet::{TimeSheet, CellValue};
+use crate::limits::{self, MAX_LAYERS};
 
 // ============================================================================
 // Binary Format Structures
 // ============================================================================
 
 /// Layer group information (for multi-section binary format)
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LayerGroup {
     pub name: String,
     pub layers: Vec<LayerData>,
 }
 
 /// Layer information
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LayerData {
     pub name: String,
     pub frames: Vec<String>,  // Frame values as strings (can be numbers, ○, etc.)
@@ -28,27 +29,43 @@ pub struct LayerData {
 /// Read a big-endian u16
 fn read_u16_be(data: &[u8], offset: usize) -> Result<u16> {
     if offset + 2 > data.len() {
-        bail!("Offset {} + 2 exceeds data length {}", offset, data.len());
+        return Err(StsError::Decode(format!("Offset {} + 2 exceeds data length {}", offset, data.len())));
     }
     Ok(u16::from_be_bytes([data[offset], data[offset + 1]]))
 }
 
+/// Result of parsing an SXF binary file, with diagnostics such as mis-decoded layer names
+#[derive(Debug)]
+pub struct SxfParseResult {
+    pub groups: Vec<LayerGroup>,
+    pub warnings: Vec<String>,
+}
+
 /// Parse SXF binary file and return groups (for 原画/台词/动画 format)
 pub fn parse_sxf_groups(path: &str) -> Result<Vec<LayerGroup>> {
+    parse_sxf_groups_with_warnings(path).map(|result| result.groups)
+}
+
+/// Parse SXF binary file like [`parse_sxf_groups`], also reporting layer names
+/// that could only be decoded with replacement characters
+pub fn parse_sxf_groups_with_warnings(path: &str) -> Result<SxfParseResult> {
     let data = std::fs::read(path)
-        .with_context(|| format!("Failed to read SXF file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to read SXF file: {}", path), e))?;
 
     if data.len() < 20 {
-        bail!("File too small: {} bytes", data.len());
+        return Err(StsError::Decode(format!("File too small: {} bytes", data.len())));
     }
 
     // Check magic
     if &data[0..4] != b"WBSC" {
-        bail!("Invalid magic: expected 'WBSC'");
+        return Err(StsError::InvalidSignature("Invalid magic: expected 'WBSC'".to_string()));
     }
 
     // Read total frame count from header (bytes 18-19, big-endian)
     let total_frames = u16::from_be_bytes([data[18], data[19]]) as usize;
+    if total_frames > limits::max_frames() {
+        return Err(StsError::TooManyFrames { found: total_frames, max: limits::max_frames() });
+    }
 
     // Find all 0xFF markers
     let mut markers = Vec::new();
@@ -59,11 +76,12 @@ pub fn parse_sxf_groups(path: &str) -> Result<Vec<LayerGroup>> {
     }
 
     let mut groups = Vec::new();
+    let mut warnings = Vec::new();
 
     // Parse section FF 03 (原画)
     if let Some(&section_03_pos) = markers.iter().find(|&&pos| pos + 1 < data.len() && data[pos + 1] == 0x03) {
         let next_marker = markers.iter().find(|&&pos| pos > section_03_pos).copied().unwrap_or(data.len());
-        if let Ok(layers) = parse_layer_data_detailed(&data[section_03_pos..next_marker], total_frames) {
+        if let Ok(layers) = parse_layer_data_detailed(&data[section_03_pos..next_marker], total_frames, &mut warnings) {
             groups.push(LayerGroup {
                 name: "原画".to_string(),
                 layers,
@@ -74,7 +92,7 @@ pub fn parse_sxf_groups(path: &str) -> Result<Vec<LayerGroup>> {
     // Parse section FF 04 (动画)
     if let Some(&section_04_pos) = markers.iter().find(|&&pos| pos + 1 < data.len() && data[pos + 1] == 0x04) {
         let next_marker = markers.iter().find(|&&pos| pos > section_04_pos).copied().unwrap_or(data.len());
-        if let Ok(layers) = parse_layer_data_detailed(&data[section_04_pos..next_marker], total_frames) {
+        if let Ok(layers) = parse_layer_data_detailed(&data[section_04_pos..next_marker], total_frames, &mut warnings) {
             groups.push(LayerGroup {
                 name: "动画".to_string(),
                 layers,
@@ -83,14 +101,14 @@ pub fn parse_sxf_groups(path: &str) -> Result<Vec<LayerGroup>> {
     }
 
     if groups.is_empty() {
-        bail!("No layer groups found");
+        return Err(StsError::Decode("No layer groups found".to_string()));
     }
 
-    Ok(groups)
+    Ok(SxfParseResult { groups, warnings })
 }
 
 /// Parse layer data from a section - returns detailed frame data expanded to total_frames
-fn parse_layer_data_detailed(section_data: &[u8], total_frames: usize) -> Result<Vec<LayerData>> {
+fn parse_layer_data_detailed(section_data: &[u8], total_frames: usize, warnings: &mut Vec<String>) -> Result<Vec<LayerData>> {
     let mut layers = Vec::new();
 
     // Search for all layer markers in the section
@@ -123,12 +141,12 @@ fn parse_layer_data_detailed(section_data: &[u8], total_frames: usize) -> Result
 
     // Process each potential layer marker
     for &pos in &layer_positions {
-        if layers.len() >= 10 {
+        if layers.len() >= MAX_LAYERS {
             break;
         }
 
         // Try to parse as a layer
-        if let Ok(layer) = parse_single_layer(section_data, pos, total_frames) {
+        if let Ok(layer) = parse_single_layer(section_data, pos, total_frames, warnings) {
             layers.push(layer);
         }
     }
@@ -137,7 +155,8 @@ fn parse_layer_data_detailed(section_data: &[u8], total_frames: usize) -> Result
 }
 
 /// Parse a single layer starting at the given position
-fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize) -> Result<LayerData> {
+/// A name that needed replacement characters is reported in `warnings` once the layer parses
+fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize, warnings: &mut Vec<String>) -> Result<LayerData> {
     // Detect format type and read name length accordingly
     let (name_offset, name_len) = if pos + 4 < section_data.len()
         && section_data[pos] == 0x02
@@ -151,7 +170,7 @@ fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize) -> R
         if len > 0 && len < 100 {
             (pos + 4, len)
         } else {
-            bail!("Invalid name length in 02 DB format: {}", len);
+            return Err(StsError::Decode(format!("Invalid name length in 02 DB format: {}", len)));
         }
     } else {
         // Format 1: 0x0B [byte] ... with name length at various offsets
@@ -167,24 +186,25 @@ fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize) -> R
                 if alt_len > 0 && alt_len < 1000 {
                     (pos + 8, alt_len as usize)
                 } else {
-                    bail!("Invalid name length at both offsets");
+                    return Err(StsError::Decode("Invalid name length at both offsets".to_string()));
                 }
             }
         } else {
-            bail!("Cannot read name length");
+            return Err(StsError::Decode("Cannot read name length".to_string()));
         }
     };
 
     if name_offset + name_len > section_data.len() {
-        bail!("Name extends beyond section data");
+        return Err(StsError::Decode("Name extends beyond section data".to_string()));
     }
 
     // Read name
     let name_bytes = &section_data[name_offset..name_offset + name_len];
-    let name = String::from_utf8_lossy(name_bytes).trim().to_string();
+    let (name, name_encoding) = crate::formats::csv::decode_with_fallback(name_bytes)?;
+    let name = name.trim().to_string();
 
     if name.is_empty() {
-        bail!("Empty layer name");
+        return Err(StsError::Decode("Empty layer name".to_string()));
     }
 
     // Parse frames - each frame occupies a 40-byte slot
@@ -193,14 +213,14 @@ fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize) -> R
 
     // Ensure we don't go beyond section boundaries
     if frame_data_start >= section_data.len() {
-        bail!("Frame data start beyond section");
+        return Err(StsError::Decode("Frame data start beyond section".to_string()));
     }
 
     let available_bytes = section_data.len() - frame_data_start;
     let max_frames = (available_bytes / FRAME_SLOT_SIZE).min(total_frames);
 
     if max_frames == 0 {
-        bail!("No frame data available");
+        return Err(StsError::Decode("No frame data available".to_string()));
     }
 
     let mut frames = Vec::with_capacity(max_frames);
@@ -277,15 +297,148 @@ fn parse_single_layer(section_data: &[u8], pos: usize, total_frames: usize) -> R
         frames.push(last_keyframe_value.clone());
     }
 
+    if name_encoding.is_none() {
+        warnings.push(crate::formats::csv::lossy_decode_warning(&format!("Layer name {:?}", name)));
+    }
+
     Ok(LayerData { name, frames })
 }
 
+// ============================================================================
+// Binary Format Writer
+// ============================================================================
+
+/// Size of one frame slot in a layer record
+const SXF_FRAME_SLOT_SIZE: usize = 40;
+
+/// Section code for a group name (FF 03 = 原画, FF 04 = 动画)
+fn sxf_section_code(group_name: &str) -> Option<u8> {
+    match group_name {
+        "原画" => Some(0x03),
+        "动画" => Some(0x04),
+        _ => None,
+    }
+}
+
+/// Encode one frame slot in the `00 01 [value]` layout
+///
+/// Only single-digit keyframes and the ○/●/× markers fit this layout; a held
+/// keyframe is an all-zero slot, and so is a blank before the first keyframe.
+fn encode_sxf_frame_slot(value: &str, last_keyframe: Option<&str>) -> Result<[u8; SXF_FRAME_SLOT_SIZE]> {
+    let mut slot = [0u8; SXF_FRAME_SLOT_SIZE];
+
+    let value_byte = match value {
+        "○" => 0x02,
+        "●" => 0x04,
+        "×" => 0x08,
+        // 读取时空槽沿用上一个关键帧，所以空帧只能出现在第一个关键帧之前
+        "" if last_keyframe.is_none() => return Ok(slot),
+        "" => {
+            return Err(StsError::InvalidData(
+                "SXF cannot represent a blank frame after a keyframe".to_string(),
+            ))
+        }
+        _ if last_keyframe == Some(value) => {
+            // Same as last keyframe - hold (no marker)
+            return Ok(slot);
+        }
+        _ if value.len() == 1 && value.as_bytes()[0].is_ascii_digit() => value.as_bytes()[0],
+        _ => {
+            return Err(StsError::InvalidData(format!(
+                "SXF frames can only hold a single digit: {}",
+                value
+            )))
+        }
+    };
+
+    slot[0..3].copy_from_slice(&[0x00, 0x01, value_byte]);
+    Ok(slot)
+}
+
+/// Write groups to an SXF binary (WBSC) file
+///
+/// Layout: `WBSC` magic, total frame count at bytes 18-19 (big-endian), then
+/// one `FF 03` (原画) / `FF 04` (动画) section per group. Each layer record is
+/// `0B 00 [name len u16 BE] [name]` followed by one 40-byte slot per frame.
+/// Keyframes are written as `00 01 [digit]`, ○/●/× as `00 01 02`/`04`/`08`
+/// and held frames as empty slots. Multi-digit numbers and blanks after the
+/// first keyframe cannot be represented and are rejected.
+pub fn write_sxf_binary(groups: &[LayerGroup], path: &str) -> Result<()> {
+    if groups.is_empty() {
+        return Err(StsError::InvalidData("No groups to write".to_string()));
+    }
+
+    let total_frames = groups.iter()
+        .flat_map(|g| &g.layers)
+        .map(|l| l.frames.len())
+        .max()
+        .unwrap_or(0);
+    if total_frames == 0 {
+        return Err(StsError::InvalidData("No layer data to write".to_string()));
+    }
+    if total_frames > u16::MAX as usize {
+        return Err(StsError::TooManyFrames { found: total_frames, max: u16::MAX as usize });
+    }
+
+    // Header: magic + padding + total frame count at bytes 18-19
+    let mut data = vec![0u8; 20];
+    data[0..4].copy_from_slice(b"WBSC");
+    data[18..20].copy_from_slice(&(total_frames as u16).to_be_bytes());
+
+    for group in groups {
+        let code = sxf_section_code(&group.name)
+            .ok_or_else(|| StsError::InvalidData(format!("Unsupported SXF group: {}", group.name)))?;
+        data.extend_from_slice(&[0xFF, code]);
+
+        for layer in &group.layers {
+            let mut name = layer.name.trim().to_string();
+            if name.is_empty() {
+                return Err(StsError::InvalidData(format!("Empty layer name in group {}", group.name)));
+            }
+            if name.bytes().any(|b| b == 0x0B) {
+                return Err(StsError::InvalidData(format!("Invalid character in layer name: {:?}", name)));
+            }
+            // 长度字节不能与 0x0B（图层标记）或 0xFF（段标记）冲突，
+            // 末尾补空格（读取时会 trim）
+            while matches!(name.len() & 0xFF, 0x0B | 0xFF) {
+                name.push(' ');
+            }
+            if name.len() >= 1000 {
+                return Err(StsError::InvalidData(format!("Layer name too long: {}", layer.name)));
+            }
+
+            data.extend_from_slice(&[0x0B, 0x00]);
+            data.extend_from_slice(&(name.len() as u16).to_be_bytes());
+            data.extend_from_slice(name.as_bytes());
+
+            let mut prev_value: Option<&str> = None;
+            let mut last_keyframe: Option<&str> = None;
+            for frame_idx in 0..total_frames {
+                let value = layer.frames.get(frame_idx)
+                    .map(|s| s.as_str())
+                    .or(prev_value)
+                    .unwrap_or("");
+                data.extend_from_slice(&encode_sxf_frame_slot(value, last_keyframe)?);
+                if !value.is_empty() && !matches!(value, "○" | "●" | "×") {
+                    last_keyframe = Some(value);
+                }
+                prev_value = Some(value);
+            }
+        }
+    }
+
+    std::fs::write(path, data)
+        .map_err(|e| StsError::io(format!("Failed to write SXF file: {}", path), e))?;
+
+    Ok(())
+}
+
 /// Write groups to CSV file in the 原画/台词/动画 format
 pub fn write_groups_to_csv(groups: &[LayerGroup], path: &str) -> Result<()> {
     use std::io::Write;
 
     let mut output = std::fs::File::create(path)
-        .with_context(|| format!("Failed to create CSV file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to create CSV file: {}", path), e))?;
 
     // Determine max frame count
     let max_frames = groups.iter()
@@ -360,7 +513,7 @@ pub fn write_groups_to_csv(groups: &[LayerGroup], path: &str) -> Result<()> {
 /// Combines all layers from all groups into one timesheet
 pub fn groups_to_timesheet(groups: &[LayerGroup], filename: &str) -> Result<TimeSheet> {
     if groups.is_empty() {
-        bail!("No groups to convert");
+        return Err(StsError::InvalidData("No groups to convert".to_string()));
     }
 
     // Calculate total layer count and frame count
@@ -371,7 +524,14 @@ pub fn groups_to_timesheet(groups: &[LayerGroup], filename: &str) -> Result<Time
         .unwrap_or(0);
 
     if total_layers == 0 || frame_count == 0 {
-        bail!("No layer data found");
+        return Err(StsError::InvalidData("No layer data found".to_string()));
+    }
+    // 每组各自限制列数，合并后仍需检查
+    if total_layers > MAX_LAYERS {
+        return Err(StsError::TooManyLayers { found: total_layers, max: MAX_LAYERS });
+    }
+    if frame_count > limits::max_frames() {
+        return Err(StsError::TooManyFrames { found: frame_count, max: limits::max_frames() });
     }
 
     // Create timesheet
@@ -403,7 +563,7 @@ pub fn groups_to_timesheet(groups: &[LayerGroup], filename: &str) -> Result<Time
                         // Treat special markers as empty for now (can be extended)
                         None
                     } else if let Ok(num) = value_str.parse::<u32>() {
-                        Some(CellValue::Number(num))
+                        CellValue::Number(num).non_blank()
                     } else {
                         None
                     };
@@ -449,7 +609,7 @@ pub fn parse_sxf_file(path: &str) -> Result<TimeSheet> {
 fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
     // Read file as binary
     let bytes = std::fs::read(path)
-        .with_context(|| format!("Failed to read SXF file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to read SXF file: {}", path), e))?;
 
     // Convert special bytes to readable characters
     let mut processed = Vec::with_capacity(bytes.len());
@@ -475,13 +635,12 @@ fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
     }
 
     // Try multiple encodings to decode
-    let content = crate::formats::csv::decode_with_fallback(&processed)
-        .with_context(|| "Failed to decode SXF file")?;
+    let (content, _) = crate::formats::csv::decode_with_fallback(&processed)?;
 
     // Limit file size to prevent issues with large files
     const MAX_CONTENT_SIZE: usize = 1_000_000; // 1MB
     if content.len() > MAX_CONTENT_SIZE {
-        bail!("SXF file too large: {} bytes (max: {})", content.len(), MAX_CONTENT_SIZE);
+        return Err(StsError::Decode(format!("SXF file too large: {} bytes (max: {})", content.len(), MAX_CONTENT_SIZE)));
     }
 
     // Split by lines
@@ -490,7 +649,7 @@ fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
     // Limit line count
     const MAX_LINES: usize = 10_000;
     if lines.len() > MAX_LINES {
-        bail!("Too many lines in SXF file: {} (max: {})", lines.len(), MAX_LINES);
+        return Err(StsError::Decode(format!("Too many lines in SXF file: {} (max: {})", lines.len(), MAX_LINES)));
     }
 
     let mut cell_array: Vec<String> = Vec::new();
@@ -641,12 +800,12 @@ fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
 
     // Check data
     if cell_array.is_empty() || frame_array.is_empty() {
-        bail!(
+        return Err(StsError::Decode(format!(
             "No valid data found in SXF file. Lines: {}, Cells found: {}, Frames found: {}",
             lines.len(),
             cell_array.len(),
             frame_array.len()
-        );
+        )));
     }
 
     // Calculate layer count and frame count
@@ -693,12 +852,12 @@ fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
                 // Special symbols, treat as empty for now
                 None
             } else if let Ok(num) = value_str.parse::<u32>() {
-                Some(CellValue::Number(num))
+                CellValue::Number(num).non_blank()
             } else {
                 // Try to extract number
                 let num_str: String = value_str.chars().filter(|c| c.is_ascii_digit()).collect();
                 if let Ok(num) = num_str.parse::<u32>() {
-                    Some(CellValue::Number(num))
+                    CellValue::Number(num).non_blank()
                 } else {
                     None
                 }
@@ -710,3 +869,160 @@ fn parse_sxf_text_format(path: &str) -> Result<TimeSheet> {
 
     Ok(timesheet)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(name: &str, frames: &[&str]) -> LayerData {
+        LayerData {
+            name: name.to_string(),
+            frames: frames.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let groups = vec![
+            LayerGroup {
+                name: "原画".to_string(),
+                layers: vec![
+                    layer("A", &["1", "1", "2", "2", "3", "3"]),
+                    layer("B", &["", "", "5", "5", "5", "7"]),
+                ],
+            },
+            LayerGroup {
+                name: "动画".to_string(),
+                // 11 字节的名称需要补齐，避免长度字节被误认为图层标记
+                layers: vec![layer("LAYER_NAME1", &["4", "4", "4", "9", "9", "9"])],
+            },
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.sxf");
+        let path = path.to_str().unwrap();
+        write_sxf_binary(&groups, path).unwrap();
+
+        let data = std::fs::read(path).unwrap();
+        assert_eq!(&data[0..4], b"WBSC");
+        assert_eq!(u16::from_be_bytes([data[18], data[19]]), 6);
+
+        let parsed = parse_sxf_groups(path).unwrap();
+        assert_eq!(parsed, groups);
+    }
+
+    #[test]
+    fn test_binary_rejects_unknown_group() {
+        let groups = vec![LayerGroup {
+            name: "台词".to_string(),
+            layers: vec![layer("A", &["1"])],
+        }];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.sxf");
+        assert!(write_sxf_binary(&groups, path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_lossy_layer_name_warning() {
+        let groups = vec![LayerGroup {
+            name: "原画".to_string(),
+            layers: vec![layer("AB", &["1", "2"]), layer("原画", &["3", "3"])],
+        }];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names.sxf");
+        let path = path.to_str().unwrap();
+        write_sxf_binary(&groups, path).unwrap();
+        assert!(parse_sxf_groups_with_warnings(path).unwrap().warnings.is_empty());
+
+        // 把 "AB" 改成 UTF-8、GBK、Shift-JIS 都无法解码的字节
+        let mut data = std::fs::read(path).unwrap();
+        let pos = data.windows(2).position(|w| w == b"AB").unwrap();
+        data[pos..pos + 2].copy_from_slice(b"\x81\x7F");
+        std::fs::write(path, data).unwrap();
+
+        let result = parse_sxf_groups_with_warnings(path).unwrap();
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.groups[0].layers[0].name.contains('\u{FFFD}'));
+        assert_eq!(result.groups[0].layers[1].name, "原画");
+    }
+
+    #[test]
+    fn test_binary_rejects_unencodable_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.sxf");
+        // 多位数、关键帧之后的空帧都无法用 00 01 [value] 表示
+        for frames in [&["1", "12"][..], &["1", ""], &["", "3", "12A"]] {
+            let groups = vec![LayerGroup {
+                name: "原画".to_string(),
+                layers: vec![layer("A", frames)],
+            }];
+            assert!(write_sxf_binary(&groups, path.to_str().unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_read_fixture() {
+        fn slot(value: Option<u8>, offset: usize) -> Vec<u8> {
+            let mut slot = vec![0u8; 40];
+            if let Some(value) = value {
+                slot[offset..offset + 3].copy_from_slice(&[0x00, 0x01, value]);
+            }
+            slot
+        }
+
+        // 按解析器支持的两种图层记录拼出的文件：0B EB 开头的 A，以及 02 DB 00 01 开头的 B
+        let mut data = vec![0u8; 20];
+        data[0..4].copy_from_slice(b"WBSC");
+        data[19] = 5;
+        data.extend_from_slice(&[0xFF, 0x03]);
+        data.extend_from_slice(&[0x0B, 0xEB, 0x00, 0x01, b'A']);
+        data.extend(slot(Some(b'3'), 4));
+        data.extend(slot(Some(0x02), 0));
+        data.extend(slot(None, 0));
+        data.extend(slot(Some(b'5'), 10));
+        data.extend(slot(Some(0x08), 0));
+        data.extend_from_slice(&[0x02, 0xDB, 0x00, 0x01, b'B']);
+        data.extend(slot(None, 0));
+        data.extend(slot(Some(b'7'), 0));
+        data.extend(slot(Some(0x04), 0));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.sxf");
+        std::fs::write(&path, data).unwrap();
+
+        let parsed = parse_sxf_groups(path.to_str().unwrap()).unwrap();
+        assert_eq!(parsed, vec![LayerGroup {
+            name: "原画".to_string(),
+            layers: vec![
+                // ○/×/空槽沿用上一个关键帧
+                layer("A", &["3", "3", "3", "5", "5"]),
+                // 数据不足的帧用最后一个关键帧补齐
+                layer("B", &["", "7", "7", "7", "7"]),
+            ],
+        }]);
+    }
+
+    #[test]
+    fn test_section_with_many_layers() {
+        let names: Vec<String> = (1..=16).map(|i| format!("L{}", i)).collect();
+        let groups = vec![LayerGroup {
+            name: "动画".to_string(),
+            layers: names.iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let value = ((i % 9) + 1).to_string();
+                    layer(name, &["", value.as_str(), value.as_str(), "3"])
+                })
+                .collect(),
+        }];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("many.sxf");
+        let path = path.to_str().unwrap();
+        write_sxf_binary(&groups, path).unwrap();
+
+        let parsed = parse_sxf_groups(path).unwrap();
+        assert_eq!(parsed, groups);
+        assert_eq!(parsed[0].layers[15].frames, vec!["", "7", "7", "3"]);
+    }
+}
@@ -1,32 +1,41 @@
-//! TDTS format parser
+//! TDTS format parser and writer
 
-use anyhow::{Context, Result};
-use serde::Deserialize;
+use crate::error::{Result, StsError};
+use serde::{Deserialize, Serialize};
 use crate::models::timesheet::{TimeSheet, CellValue};
-use crate::limits::{MAX_LAYERS, MAX_FRAMES};
-use super::fill_keyframes;
+use crate::limits::{max_frames, MAX_LAYERS};
+use super::{fill_keyframes, keyframe_tracks, parse_track_symbol};
 
-#[derive(Debug, Deserialize)]
+/// First line of every TDTS file
+const TDTS_HEADER_LINE: &str = "toeiDigitalTimeSheet Save Data";
+
+/// Field id of the CELL field
+const TDTS_CELL_FIELD_ID: u32 = 4;
+
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsRoot {
     #[serde(rename = "timeSheets")]
     time_sheets: Vec<TdtsTimeSheet>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsTimeSheet {
     #[serde(default)]
     header: TdtsHeader,
+    /// 帧率（缺省或为 0 时按 24fps 处理）
+    #[serde(rename = "frameRate", default)]
+    frame_rate: u32,
     #[serde(rename = "timeTables", default)]
     time_tables: Vec<TdtsTimeTable>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 struct TdtsHeader {
     #[serde(default)]
     cut: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsTimeTable {
     #[serde(default)]
     name: String,
@@ -38,7 +47,7 @@ struct TdtsTimeTable {
     time_table_headers: Vec<TdtsTimeTableHeader>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsField {
     #[serde(rename = "fieldId", default)]
     field_id: u32,
@@ -46,7 +55,7 @@ struct TdtsField {
     tracks: Vec<TdtsTrack>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsTrack {
     #[serde(rename = "trackNo", default)]
     track_no: usize,
@@ -54,7 +63,7 @@ struct TdtsTrack {
     frames: Vec<TdtsFrame>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsFrame {
     #[serde(default)]
     frame: i32,
@@ -62,13 +71,15 @@ struct TdtsFrame {
     data: Vec<TdtsData>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsData {
+    #[serde(default)]
+    id: u32,
     #[serde(default)]
     values: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TdtsTimeTableHeader {
     #[serde(rename = "fieldId", default)]
     field_id: u32,
@@ -80,12 +91,14 @@ struct TdtsTimeTableHeader {
 pub struct TdtsParseResult {
     pub timesheets: Vec<TimeSheet>,
     pub warnings: Vec<String>,
+    /// Time tables that produced no timesheet, as "name (reason)"
+    pub skipped: Vec<String>,
 }
 
 /// Parse TDTS file and return multiple TimeSheets (one per timeTable)
 pub fn parse_tdts_file(path: &str) -> Result<TdtsParseResult> {
     let content = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read TDTS file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to read TDTS file: {}", path), e))?;
 
     // Skip first line (TDTS header)
     let json_content = content
@@ -95,16 +108,22 @@ pub fn parse_tdts_file(path: &str) -> Result<TdtsParseResult> {
         .join("\n");
 
     let root: TdtsRoot = serde_json::from_str(&json_content)
-        .with_context(|| "Failed to parse TDTS JSON")?;
+        .map_err(|e| StsError::json("Failed to parse TDTS JSON", e))?;
 
     let mut timesheets = Vec::new();
     let mut warnings = Vec::new();
+    let mut skipped = Vec::new();
 
     for time_sheet in root.time_sheets {
         let cut_name = &time_sheet.header.cut;
+        let framerate = match time_sheet.frame_rate {
+            0 => 24, // Default framerate
+            fps => fps,
+        };
 
         for time_table in time_sheet.time_tables {
             if time_table.fields.is_empty() {
+                skipped.push(format!("{} (no fields)", time_table.name));
                 continue;
             }
 
@@ -138,15 +157,15 @@ pub fn parse_tdts_file(path: &str) -> Result<TdtsParseResult> {
                 let frame_count = time_table.duration;
 
                 if layer_count > MAX_LAYERS {
-                    anyhow::bail!("Too many layers in TDTS file: {} (max: {})", layer_count, MAX_LAYERS);
+                    return Err(StsError::TooManyLayers { found: layer_count, max: MAX_LAYERS });
                 }
-                if frame_count > MAX_FRAMES {
-                    anyhow::bail!("Too many frames in TDTS file: {} (max: {})", frame_count, MAX_FRAMES);
+                if frame_count > max_frames() {
+                    return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
                 }
 
                 let mut timesheet = TimeSheet::new(
                     name,
-                    24, // Default framerate
+                    framerate,
                     layer_count,
                     144, // Default frames per page
                 );
@@ -185,13 +204,10 @@ pub fn parse_tdts_file(path: &str) -> Result<TdtsParseResult> {
 
                         if let Some(data) = frame_data.data.first() {
                             if let Some(value_str) = data.values.first() {
-                                let cell_value = if value_str == "SYMBOL_NULL_CELL" {
-                                    None
-                                } else if let Ok(num) = value_str.parse::<u32>() {
-                                    Some(CellValue::Number(num))
-                                } else {
-                                    None
-                                };
+                                // Symbols are read as in XDTS; anything else unparseable is blank
+                                let cell_value = parse_track_symbol(value_str).unwrap_or_else(|| {
+                                    CellValue::parse_drawing(value_str).and_then(CellValue::non_blank)
+                                });
                                 keyframes.push((frame_idx, cell_value));
                             }
                         }
@@ -203,9 +219,189 @@ pub fn parse_tdts_file(path: &str) -> Result<TdtsParseResult> {
                 }
 
                 timesheets.push(timesheet);
+            } else {
+                skipped.push(format!("{} (no named cell field)", time_table.name));
             }
         }
     }
 
-    Ok(TdtsParseResult { timesheets, warnings })
+    Ok(TdtsParseResult { timesheets, warnings, skipped })
+}
+
+/// Write a TimeSheet to a TDTS file (one timeSheet with one timeTable)
+///
+/// Cells go into the CELL field (fieldId 4); only keyframes (value changes)
+/// are written and blanks use `SYMBOL_NULL_CELL`.
+pub fn write_tdts_file(timesheet: &TimeSheet, cut_name: &str, path: &str) -> Result<()> {
+    let frame_count = timesheet.total_frames();
+
+    let tracks: Vec<TdtsTrack> = keyframe_tracks(timesheet)
+        .into_iter()
+        .map(|(track_no, frames)| TdtsTrack {
+            track_no,
+            frames: frames.into_iter()
+                .map(|(frame, value)| TdtsFrame {
+                    frame: frame as i32,
+                    data: vec![TdtsData { id: 0, values: vec![value] }],
+                })
+                .collect(),
+        })
+        .collect();
+
+    let root = TdtsRoot {
+        time_sheets: vec![TdtsTimeSheet {
+            header: TdtsHeader { cut: cut_name.to_string() },
+            frame_rate: timesheet.framerate,
+            time_tables: vec![TdtsTimeTable {
+                name: timesheet.name.clone(),
+                duration: frame_count,
+                fields: vec![TdtsField {
+                    field_id: TDTS_CELL_FIELD_ID,
+                    tracks,
+                }],
+                time_table_headers: vec![TdtsTimeTableHeader {
+                    field_id: TDTS_CELL_FIELD_ID,
+                    names: timesheet.layer_names.clone(),
+                }],
+            }],
+        }],
+    };
+
+    let json = serde_json::to_string_pretty(&root)
+        .map_err(|e| StsError::json("Failed to serialize TDTS JSON", e))?;
+
+    std::fs::write(path, format!("{}\n{}\n", TDTS_HEADER_LINE, json))
+        .map_err(|e| StsError::io(format!("Failed to write TDTS file: {}", path), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"toeiDigitalTimeSheet Save Data
+{
+  "timeSheets": [
+    {
+      "header": { "cut": "12" },
+      "frameRate": 30,
+      "timeTables": [
+        {
+          "name": "sheet",
+          "duration": 8,
+          "fields": [
+            {
+              "fieldId": 4,
+              "tracks": [
+                {
+                  "trackNo": 0,
+                  "frames": [
+                    { "frame": 0, "data": [ { "values": [ "1" ] } ] },
+                    { "frame": 3, "data": [ { "values": [ "2" ] } ] },
+                    { "frame": 6, "data": [ { "values": [ "SYMBOL_NULL_CELL" ] } ] }
+                  ]
+                }
+              ]
+            }
+          ],
+          "timeTableHeaders": [ { "fieldId": 4, "names": [ "A" ] } ]
+        }
+      ]
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn test_framerate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sample.tdts");
+        std::fs::write(&path, SAMPLE).unwrap();
+
+        let result = parse_tdts_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(result.timesheets.len(), 1);
+        let sheet = &result.timesheets[0];
+        assert_eq!(sheet.framerate, 30);
+        assert_eq!(sheet.get_actual_value(0, 4), Some(2));
+        assert_eq!(sheet.get_actual_value(0, 7), None);
+
+        let no_rate = SAMPLE.replace("\"frameRate\": 30,", "");
+        std::fs::write(&path, no_rate).unwrap();
+        let result = parse_tdts_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(result.timesheets[0].framerate, 24);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.tdts");
+        let out = dir.path().join("out.tdts");
+        std::fs::write(&src, SAMPLE).unwrap();
+
+        let original = parse_tdts_file(src.to_str().unwrap()).unwrap();
+        let original = &original.timesheets[0];
+
+        write_tdts_file(original, "12", out.to_str().unwrap()).unwrap();
+        let content = std::fs::read_to_string(&out).unwrap();
+        assert!(content.starts_with(TDTS_HEADER_LINE));
+
+        let reloaded = parse_tdts_file(out.to_str().unwrap()).unwrap();
+        assert!(reloaded.warnings.is_empty());
+        assert_eq!(reloaded.timesheets.len(), 1);
+        let reloaded = &reloaded.timesheets[0];
+
+        assert_eq!(reloaded.framerate, original.framerate);
+        assert_eq!(reloaded.layer_names, original.layer_names);
+        assert_eq!(reloaded.total_frames(), original.total_frames());
+        for frame in 0..original.total_frames() {
+            assert_eq!(reloaded.get_cell(0, frame), original.get_cell(0, frame));
+        }
+    }
+
+    #[test]
+    fn test_tick_is_held() {
+        let content = r#"toeiDigitalTimeSheet Save Data
+{
+  "timeSheets": [
+    {
+      "timeTables": [
+        {
+          "name": "sheet",
+          "duration": 6,
+          "fields": [
+            {
+              "fieldId": 4,
+              "tracks": [
+                {
+                  "trackNo": 0,
+                  "frames": [
+                    { "frame": 0, "data": [ { "values": [ "1" ] } ] },
+                    { "frame": 2, "data": [ { "values": [ "SYMBOL_TICK_1" ] } ] },
+                    { "frame": 3, "data": [ { "values": [ "SYMBOL_HYPHEN" ] } ] },
+                    { "frame": 4, "data": [ { "values": [ "3" ] } ] }
+                  ]
+                }
+              ]
+            }
+          ],
+          "timeTableHeaders": [ { "fieldId": 4, "names": [ "A" ] } ]
+        }
+      ]
+    }
+  ]
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tick.tdts");
+        std::fs::write(&path, content).unwrap();
+
+        let result = parse_tdts_file(path.to_str().unwrap()).unwrap();
+        let sheet = &result.timesheets[0];
+        assert_eq!(sheet.get_cell(0, 1), Some(&CellValue::Number(1)));
+        assert_eq!(sheet.get_cell(0, 2), Some(&CellValue::Same));
+        assert_eq!(sheet.get_cell(0, 3), Some(&CellValue::Same));
+        assert_eq!(sheet.get_actual_value(0, 3), Some(1));
+        assert_eq!(sheet.get_cell(0, 4), Some(&CellValue::Number(3)));
+    }
 }
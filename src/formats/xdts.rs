@@ -1,67 +1,105 @@
-//! XDTS format parser
+//! XDTS format parser and writer
 
-use anyhow::{Context, Result};
-use serde::Deserialize;
+use crate::error::{Result, StsError};
+use serde::{Deserialize, Serialize};
 use crate::models::timesheet::{TimeSheet, CellValue};
-use crate::limits::{MAX_LAYERS, MAX_FRAMES};
-use super::fill_keyframes;
+use crate::limits::{max_frames, MAX_LAYERS};
+use super::{fill_keyframes, keyframe_tracks, parse_track_symbol};
 use std::sync::OnceLock;
 
 static RE_NUM: OnceLock<regex::Regex> = OnceLock::new();
 
-#[derive(Debug, Deserialize)]
+/// First line of every XDTS file
+const XDTS_HEADER_LINE: &str = "exchangeDigitalTimeSheet Save Data";
+
+/// Field id used for cell (drawing) tracks
+const XDTS_CELL_FIELD_ID: u32 = 0;
+
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsRoot {
+    #[serde(default)]
+    header: XdtsHeader,
     #[serde(rename = "timeTables")]
     time_tables: Vec<XdtsTimeTable>,
+    #[serde(default = "default_xdts_version")]
+    version: u32,
+}
+
+fn default_xdts_version() -> u32 {
+    5
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct XdtsHeader {
+    #[serde(default)]
+    cut: String,
+    #[serde(default)]
+    scene: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsTimeTable {
     name: String,
     duration: usize,
+    /// 帧率（缺省或为 0 时按 24fps 处理）
+    #[serde(rename = "frameRate", default)]
+    frame_rate: u32,
     #[serde(default)]
     fields: Vec<XdtsField>,
     #[serde(rename = "timeTableHeaders")]
     time_table_headers: Vec<XdtsTimeTableHeader>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsField {
     #[serde(rename = "fieldId")]
     field_id: u32,
     tracks: Vec<XdtsTrack>,
-
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsTrack {
     #[serde(rename = "trackNo")]
     track_no: usize,
     frames: Vec<XdtsFrame>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsFrame {
     frame: usize,
     data: Vec<XdtsData>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsData {
+    #[serde(default)]
+    id: u32,
     values: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct XdtsTimeTableHeader {
     #[serde(rename = "fieldId")]
     field_id: u32,
     names: Vec<String>,
 }
 
+/// Parse result containing timesheets and the time tables that were skipped
+pub struct XdtsParseResult {
+    pub timesheets: Vec<TimeSheet>,
+    /// Time tables that produced no timesheet, as "name (reason)"
+    pub skipped: Vec<String>,
+}
+
 /// Parse XDTS file and return multiple TimeSheets (one per timeTable)
 pub fn parse_xdts_file(path: &str) -> Result<Vec<TimeSheet>> {
+    parse_xdts_file_with_report(path).map(|result| result.timesheets)
+}
+
+/// Parse XDTS file like [`parse_xdts_file`], also listing skipped time tables
+pub fn parse_xdts_file_with_report(path: &str) -> Result<XdtsParseResult> {
     let content = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read XDTS file: {}", path))?;
+        .map_err(|e| StsError::io(format!("Failed to read XDTS file: {}", path), e))?;
 
     // Skip first line (XDTS header)
     let json_content = content
@@ -71,13 +109,14 @@ pub fn parse_xdts_file(path: &str) -> Result<Vec<TimeSheet>> {
         .join("\n");
 
     let root: XdtsRoot = serde_json::from_str(&json_content)
-        .with_context(|| "Failed to parse XDTS JSON")?;
+        .map_err(|e| StsError::json("Failed to parse XDTS JSON", e))?;
 
     let mut timesheets = Vec::new();
-    let re_num = RE_NUM.get_or_init(|| regex::Regex::new(r"\d+$").unwrap());
+    let mut skipped = Vec::new();
 
     for time_table in root.time_tables {
         if time_table.fields.is_empty() {
+            skipped.push(format!("{} (no fields)", time_table.name));
             continue;
         }
 
@@ -87,94 +126,462 @@ pub fn parse_xdts_file(path: &str) -> Result<Vec<TimeSheet>> {
             .unwrap_or("untitled");
         let name = format!("{}->{}", file_name, time_table.name);
 
-        // Use first field's fieldId
-        let field = &time_table.fields[0];
-        let field_id = field.field_id;
-        let tracks = &field.tracks;
+        // Every non-empty field with a header becomes its own block of layers
+        let find_names = |field_id: u32| {
+            time_table.time_table_headers.iter()
+                .find(|h| h.field_id == field_id)
+                .map(|h| &h.names)
+        };
+        let mut blocks: Vec<(&XdtsField, &Vec<String>)> = time_table.fields.iter()
+            .filter(|f| !f.tracks.is_empty())
+            .filter_map(|f| find_names(f.field_id).map(|names| (f, names)))
+            .collect();
+        if blocks.is_empty() {
+            // Fall back to the first field even when it has no tracks
+            let field = &time_table.fields[0];
+            if let Some(names) = find_names(field.field_id) {
+                blocks.push((field, names));
+            }
+        }
+        if blocks.is_empty() {
+            skipped.push(format!("{} (no named fields)", time_table.name));
+            continue;
+        }
 
-        // Find names matching this fieldId
-        let names = time_table.time_table_headers.iter()
-            .find(|h| h.field_id == field_id)
-            .map(|h| &h.names);
+        // Layer count of each block
+        let block_sizes: Vec<usize> = blocks.iter()
+            .map(|(field, names)| field.tracks.len().max(names.len()))
+            .collect();
+        let layer_count: usize = block_sizes.iter().sum();
+        let frame_count = time_table.duration;
 
-        if let Some(names) = names {
-            let layer_count = tracks.len().max(names.len());
-            let frame_count = time_table.duration;
+        if layer_count > MAX_LAYERS {
+            return Err(StsError::TooManyLayers { found: layer_count, max: MAX_LAYERS });
+        }
+        if frame_count > max_frames() {
+            return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
+        }
 
-            if layer_count > MAX_LAYERS {
-                anyhow::bail!("Too many layers in XDTS file: {} (max: {})", layer_count, MAX_LAYERS);
-            }
-            if frame_count > MAX_FRAMES {
-                anyhow::bail!("Too many frames in XDTS file: {} (max: {})", frame_count, MAX_FRAMES);
-            }
+        let framerate = match time_table.frame_rate {
+            0 => 24, // Default framerate
+            fps => fps,
+        };
 
-            let mut timesheet = TimeSheet::new(
-                name,
-                24, // Default framerate
-                layer_count,
-                144, // Default frames per page
-            );
-            timesheet.ensure_frames(frame_count);
+        let mut timesheet = TimeSheet::new(
+            name,
+            framerate,
+            layer_count,
+            144, // Default frames per page
+        );
+        timesheet.ensure_frames(frame_count);
 
+        // 多个 field 时，图层名加上 fieldId 前缀以区分
+        let prefix_names = blocks.len() > 1;
+        let mut layer_offset = 0;
+        for ((field, names), block_size) in blocks.iter().zip(&block_sizes) {
             // Set layer names
-            for (i, name) in names.iter().enumerate() {
-                if i < timesheet.layer_names.len() {
-                    timesheet.layer_names[i] = name.clone();
-                }
+            for (i, layer_name) in names.iter().enumerate().take(*block_size) {
+                timesheet.layer_names[layer_offset + i] = if prefix_names {
+                    format!("{}:{}", field.field_id, layer_name)
+                } else {
+                    layer_name.clone()
+                };
             }
 
             // Parse frame data
-            for track in tracks {
-                let layer_idx = track.track_no;
-                if layer_idx >= layer_count {
+            for track in &field.tracks {
+                if track.track_no >= *block_size {
                     continue;
                 }
 
-                // Collect keyframes (frame_idx, value)
-                let mut keyframes: Vec<(usize, Option<CellValue>)> = Vec::new();
-                for frame_data in &track.frames {
-                    let frame_idx = frame_data.frame;
-                    if frame_idx >= frame_count {
-                        continue;
-                    }
-
-                    if let Some(data) = frame_data.data.first() {
-                        if let Some(value_str) = data.values.first() {
-                            let cell_value = if value_str == "SYMBOL_NULL_CELL" {
-                                Some(CellValue::Number(0))
-                            } else if value_str == "SYMBOL_TICK_1"
-                                   || value_str == "SYMBOL_TICK_2"
-                                   || value_str == "SYMBOL_HYPHEN" {
-                                // Skip these special symbols
-                                continue;
-                            } else {
-                                // Try to extract number from end of string
-                                if let Some(captures) = re_num.find(value_str) {
-                                    if let Ok(num) = captures.as_str().parse::<u32>() {
-                                        Some(CellValue::Number(num))
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                }
-                            };
-
-                            if let Some(cv) = cell_value {
-                                keyframes.push((frame_idx, Some(cv)));
-                            }
-                        }
-                    }
+                let keyframes = collect_track_keyframes(track, frame_count);
+                fill_keyframes(&mut timesheet, layer_offset + track.track_no, &keyframes, frame_count);
+            }
+
+            layer_offset += block_size;
+        }
+
+        timesheets.push(timesheet);
+    }
+
+    Ok(XdtsParseResult { timesheets, skipped })
+}
+
+/// Collect the keyframes (frame_idx, value) of one track, sorted by frame
+fn collect_track_keyframes(track: &XdtsTrack, frame_count: usize) -> Vec<(usize, Option<CellValue>)> {
+    let re_num = RE_NUM.get_or_init(|| regex::Regex::new(r"\d+$").unwrap());
+    let mut keyframes: Vec<(usize, Option<CellValue>)> = Vec::new();
+    for frame_data in &track.frames {
+        let frame_idx = frame_data.frame;
+        if frame_idx >= frame_count {
+            continue;
+        }
+
+        if let Some(data) = frame_data.data.first() {
+            if let Some(value_str) = data.values.first() {
+                // None = unrecognized mark (skipped); Some(None) = blank
+                let cell_value = parse_track_symbol(value_str).or_else(|| {
+                    // Drawing number, optionally suffixed (e.g. "12A"), else the
+                    // trailing digits of a prefixed value (e.g. "A3"); 0 is blank
+                    CellValue::parse_drawing(value_str)
+                        .or_else(|| {
+                            re_num.find(value_str)
+                                .and_then(|m| m.as_str().parse::<u32>().ok())
+                                .map(CellValue::Number)
+                        })
+                        .map(CellValue::non_blank)
+                });
+
+                if let Some(cv) = cell_value {
+                    keyframes.push((frame_idx, cv));
                 }
+            }
+        }
+    }
+
+    // Sort by frame index
+    keyframes.sort_by_key(|k| k.0);
+    keyframes
+}
+
+/// Write a TimeSheet to an XDTS file (one timeTable, one cell field)
+///
+/// Only keyframes (value changes) are written, mirroring the CSV exporter;
+/// a change to an empty cell is written as `SYMBOL_NULL_CELL`.
+pub fn write_xdts_file(timesheet: &TimeSheet, path: &str) -> Result<()> {
+    let frame_count = timesheet.total_frames();
+
+    let tracks: Vec<XdtsTrack> = keyframe_tracks(timesheet)
+        .into_iter()
+        .map(|(track_no, frames)| XdtsTrack {
+            track_no,
+            frames: frames.into_iter()
+                .map(|(frame, value)| XdtsFrame {
+                    frame,
+                    data: vec![XdtsData { id: 0, values: vec![value] }],
+                })
+                .collect(),
+        })
+        .collect();
+
+    let root = XdtsRoot {
+        header: XdtsHeader {
+            cut: "1".to_string(),
+            scene: "1".to_string(),
+        },
+        time_tables: vec![XdtsTimeTable {
+            name: timesheet.name.clone(),
+            duration: frame_count,
+            frame_rate: timesheet.framerate,
+            fields: vec![XdtsField {
+                field_id: XDTS_CELL_FIELD_ID,
+                tracks,
+            }],
+            time_table_headers: vec![XdtsTimeTableHeader {
+                field_id: XDTS_CELL_FIELD_ID,
+                names: timesheet.layer_names.clone(),
+            }],
+        }],
+        version: default_xdts_version(),
+    };
+
+    let json = serde_json::to_string_pretty(&root)
+        .map_err(|e| StsError::json("Failed to serialize XDTS JSON", e))?;
+
+    std::fs::write(path, format!("{}\n{}\n", XDTS_HEADER_LINE, json))
+        .map_err(|e| StsError::io(format!("Failed to write XDTS file: {}", path), e))?;
 
-                // Sort by frame index and fill
-                keyframes.sort_by_key(|k| k.0);
-                fill_keyframes(&mut timesheet, layer_idx, &keyframes, frame_count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"exchangeDigitalTimeSheet Save Data
+{
+  "header": { "cut": "1", "scene": "1" },
+  "timeTables": [
+    {
+      "duration": 12,
+      "fields": [
+        {
+          "fieldId": 0,
+          "tracks": [
+            {
+              "trackNo": 0,
+              "frames": [
+                { "data": [ { "id": 0, "values": [ "1" ] } ], "frame": 0 },
+                { "data": [ { "id": 0, "values": [ "2" ] } ], "frame": 4 },
+                { "data": [ { "id": 0, "values": [ "SYMBOL_NULL_CELL" ] } ], "frame": 8 }
+              ]
+            },
+            {
+              "trackNo": 1,
+              "frames": [
+                { "data": [ { "id": 0, "values": [ "A3" ] } ], "frame": 2 }
+              ]
+            }
+          ]
+        }
+      ],
+      "frameRate": 30,
+      "name": "sheet",
+      "timeTableHeaders": [ { "fieldId": 0, "names": [ "A", "B" ] } ]
+    }
+  ],
+  "version": 5
+}
+"#;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.xdts");
+        let out = dir.path().join("out.xdts");
+        std::fs::write(&src, SAMPLE).unwrap();
+
+        let original = parse_xdts_file(src.to_str().unwrap()).unwrap();
+        assert_eq!(original.len(), 1);
+        let original = &original[0];
+        assert_eq!(original.framerate, 30);
+        // 带前缀的 "A3" 取末尾数字
+        assert_eq!(original.get_cell(1, 1), None);
+        assert_eq!(original.get_cell(1, 2), Some(&CellValue::Number(3)));
+
+        write_xdts_file(original, out.to_str().unwrap()).unwrap();
+        let content = std::fs::read_to_string(&out).unwrap();
+        assert!(content.starts_with(XDTS_HEADER_LINE));
+
+        let reloaded = parse_xdts_file(out.to_str().unwrap()).unwrap();
+        assert_eq!(reloaded.len(), 1);
+        let reloaded = &reloaded[0];
+
+        assert_eq!(reloaded.framerate, 30);
+        assert_eq!(reloaded.layer_names, original.layer_names);
+        assert_eq!(reloaded.total_frames(), original.total_frames());
+        for layer in 0..original.layer_count {
+            for frame in 0..original.total_frames() {
+                assert_eq!(reloaded.get_cell(layer, frame), original.get_cell(layer, frame));
             }
+        }
+    }
+
+    #[test]
+    fn test_suffix_round_trip() {
+        let mut ts = TimeSheet::new("suffix".to_string(), 24, 1, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(0, 0, Some(CellValue::Number(12)));
+        ts.set_cell(0, 1, Some(CellValue::Labeled(12, 'A')));
+        ts.set_cell(0, 3, Some(CellValue::Number(13)));
 
-            timesheets.push(timesheet);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("suffix.xdts");
+        let path = path.to_str().unwrap();
+        write_xdts_file(&ts, path).unwrap();
+
+        let reloaded = &parse_xdts_file(path).unwrap()[0];
+        for frame in 0..4 {
+            assert_eq!(reloaded.get_actual_cell(0, frame), ts.get_actual_cell(0, frame));
         }
     }
 
-    Ok(timesheets)
+    #[test]
+    fn test_skipped_time_tables() {
+        let content = r#"exchangeDigitalTimeSheet Save Data
+{
+  "timeTables": [
+    {
+      "duration": 2,
+      "fields": [
+        { "fieldId": 0, "tracks": [ { "trackNo": 0, "frames": [ { "data": [ { "values": [ "1" ] } ], "frame": 0 } ] } ] }
+      ],
+      "name": "cut1",
+      "timeTableHeaders": [ { "fieldId": 0, "names": [ "A" ] } ]
+    },
+    { "duration": 2, "fields": [], "name": "cut2", "timeTableHeaders": [] }
+  ]
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skip.xdts");
+        std::fs::write(&path, content).unwrap();
+
+        let result = parse_xdts_file_with_report(path.to_str().unwrap()).unwrap();
+        assert_eq!(result.timesheets.len(), 1);
+        assert_eq!(result.skipped, vec!["cut2 (no fields)".to_string()]);
+    }
+
+    #[test]
+    fn test_tick_is_held() {
+        let content = r#"exchangeDigitalTimeSheet Save Data
+{
+  "timeTables": [
+    {
+      "duration": 6,
+      "fields": [
+        {
+          "fieldId": 0,
+          "tracks": [
+            {
+              "trackNo": 0,
+              "frames": [
+                { "data": [ { "values": [ "1" ] } ], "frame": 0 },
+                { "data": [ { "values": [ "SYMBOL_TICK_1" ] } ], "frame": 2 },
+                { "data": [ { "values": [ "3" ] } ], "frame": 4 }
+              ]
+            }
+          ]
+        }
+      ],
+      "name": "sheet",
+      "timeTableHeaders": [ { "fieldId": 0, "names": [ "A" ] } ]
+    }
+  ]
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tick.xdts");
+        std::fs::write(&path, content).unwrap();
+
+        let sheets = parse_xdts_file(path.to_str().unwrap()).unwrap();
+        let sheet = &sheets[0];
+        assert_eq!(sheet.get_cell(0, 1), Some(&CellValue::Number(1)));
+        assert_eq!(sheet.get_cell(0, 2), Some(&CellValue::Same));
+        assert_eq!(sheet.get_cell(0, 3), Some(&CellValue::Same));
+        assert_eq!(sheet.get_actual_value(0, 2), Some(1));
+        assert_eq!(sheet.get_cell(0, 4), Some(&CellValue::Number(3)));
+    }
+
+    #[test]
+    fn test_multiple_fields() {
+        let content = r#"exchangeDigitalTimeSheet Save Data
+{
+  "timeTables": [
+    {
+      "duration": 6,
+      "fields": [
+        {
+          "fieldId": 0,
+          "tracks": [
+            { "trackNo": 0, "frames": [ { "data": [ { "values": [ "1" ] } ], "frame": 0 } ] },
+            { "trackNo": 1, "frames": [ { "data": [ { "values": [ "2" ] } ], "frame": 0 } ] }
+          ]
+        },
+        { "fieldId": 5, "tracks": [] },
+        {
+          "fieldId": 3,
+          "tracks": [
+            { "trackNo": 0, "frames": [ { "data": [ { "values": [ "7" ] } ], "frame": 3 } ] }
+          ]
+        }
+      ],
+      "name": "sheet",
+      "timeTableHeaders": [
+        { "fieldId": 0, "names": [ "A", "B" ] },
+        { "fieldId": 3, "names": [ "CAM" ] },
+        { "fieldId": 5, "names": [ "X" ] }
+      ]
+    }
+  ]
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("multi.xdts");
+        std::fs::write(&path, content).unwrap();
+
+        let sheets = parse_xdts_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(sheets.len(), 1);
+        let sheet = &sheets[0];
+
+        assert_eq!(sheet.framerate, 24);
+        assert_eq!(sheet.layer_count, 3);
+        assert_eq!(sheet.layer_names, vec!["0:A", "0:B", "3:CAM"]);
+        assert_eq!(sheet.get_actual_value(0, 5), Some(1));
+        assert_eq!(sheet.get_actual_value(1, 0), Some(2));
+        assert_eq!(sheet.get_actual_value(2, 2), None);
+        assert_eq!(sheet.get_actual_value(2, 3), Some(7));
+    }
+
+    #[test]
+    fn test_zero_and_null_match_tdts() {
+        let xdts = r#"exchangeDigitalTimeSheet Save Data
+{
+  "timeTables": [
+    {
+      "duration": 8,
+      "fields": [
+        {
+          "fieldId": 0,
+          "tracks": [
+            {
+              "trackNo": 0,
+              "frames": [
+                { "data": [ { "id": 0, "values": [ "1" ] } ], "frame": 0 },
+                { "data": [ { "id": 0, "values": [ "0" ] } ], "frame": 2 },
+                { "data": [ { "id": 0, "values": [ "2" ] } ], "frame": 4 },
+                { "data": [ { "id": 0, "values": [ "SYMBOL_NULL_CELL" ] } ], "frame": 6 }
+              ]
+            }
+          ]
+        }
+      ],
+      "name": "sheet",
+      "timeTableHeaders": [ { "fieldId": 0, "names": [ "A" ] } ]
+    }
+  ],
+  "version": 5
+}
+"#;
+        let tdts = r#"toeiDigitalTimeSheet Save Data
+{
+  "timeSheets": [
+    {
+      "timeTables": [
+        {
+          "name": "sheet",
+          "duration": 8,
+          "fields": [
+            {
+              "fieldId": 4,
+              "tracks": [
+                {
+                  "trackNo": 0,
+                  "frames": [
+                    { "frame": 0, "data": [ { "values": [ "1" ] } ] },
+                    { "frame": 2, "data": [ { "values": [ "0" ] } ] },
+                    { "frame": 4, "data": [ { "values": [ "2" ] } ] },
+                    { "frame": 6, "data": [ { "values": [ "SYMBOL_NULL_CELL" ] } ] }
+                  ]
+                }
+              ]
+            }
+          ],
+          "timeTableHeaders": [ { "fieldId": 4, "names": [ "A" ] } ]
+        }
+      ]
+    }
+  ]
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let xdts_path = dir.path().join("a.xdts");
+        let tdts_path = dir.path().join("a.tdts");
+        std::fs::write(&xdts_path, xdts).unwrap();
+        std::fs::write(&tdts_path, tdts).unwrap();
+
+        let from_xdts = &parse_xdts_file(xdts_path.to_str().unwrap()).unwrap()[0];
+        let from_tdts = &crate::formats::parse_tdts_file(tdts_path.to_str().unwrap()).unwrap().timesheets[0];
+
+        // 0 与 SYMBOL_NULL_CELL 都导入为空白
+        assert_eq!(from_xdts.total_frames(), from_tdts.total_frames());
+        for frame in 0..from_xdts.total_frames() {
+            assert_eq!(from_xdts.get_cell(0, frame), from_tdts.get_cell(0, frame), "frame {}", frame);
+        }
+        assert_eq!(from_xdts.get_cell(0, 1), Some(&CellValue::Number(1)));
+        assert_eq!(from_xdts.get_cell(0, 2), None);
+        assert_eq!(from_xdts.get_cell(0, 3), None);
+        assert_eq!(from_xdts.get_cell(0, 5), Some(&CellValue::Number(2)));
+        assert_eq!(from_xdts.get_cell(0, 6), None);
+    }
 }
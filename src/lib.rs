@@ -1,23 +1,47 @@
 pub mod models;
 pub mod formats;
+pub mod error;
+pub mod convert;
 
-/// Shared constants for resource limits
+/// Shared resource limits enforced by every parser
 pub mod limits {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     /// Maximum number of layers allowed in a timesheet
     pub const MAX_LAYERS: usize = 1000;
-    /// Maximum number of frames allowed in a timesheet
+    /// Default maximum number of frames allowed in a timesheet
     pub const MAX_FRAMES: usize = 100_000;
+
+    static FRAME_LIMIT: AtomicUsize = AtomicUsize::new(MAX_FRAMES);
+
+    /// Frame cap currently enforced by parsers and editing operations
+    #[inline]
+    pub fn max_frames() -> usize {
+        FRAME_LIMIT.load(Ordering::Relaxed)
+    }
+
+    /// Override the process-wide frame cap, e.g. for long-form work
+    ///
+    /// The value is clamped to at least 1; pass [`MAX_FRAMES`] to restore the default.
+    pub fn set_max_frames(limit: usize) {
+        FRAME_LIMIT.store(limit.max(1), Ordering::Relaxed);
+    }
 }
 
 // Re-export commonly used types
-pub use models::{TimeSheet, Layer};
+pub use models::{TimeSheet, TimeSheetBuilder, Layer, LayerStats, LayerView};
 pub use models::timesheet::CellValue;
+pub use error::StsError;
+pub use convert::convert;
 pub use formats::{
-    parse_ae_keyframe_file, write_ae_keyframe_file,
-    parse_sts_file, write_sts_file,
-    parse_xdts_file, parse_tdts_file, TdtsParseResult,
-    parse_csv_file, write_csv_file, write_csv_file_with_options,
+    parse_ae_keyframe_file, write_ae_keyframe_file, format_ae_keyframes, format_ae_keyframes_for_layers,
+    format_ae_jsx, write_ae_jsx,
+    parse_sts_file, write_sts_file, sts_layer_name_warning, sts_suffix_warning, STS_MAX_FRAMES, STS_MAX_NAME_BYTES,
+    parse_xdts_file, parse_xdts_file_with_report, write_xdts_file, XdtsParseResult, parse_tdts_file, write_tdts_file, TdtsParseResult,
+    parse_csv_file, parse_csv_file_with_warnings, write_csv_file, write_csv_file_with_options, CsvParseResult,
+    parse_json_file, write_json_file,
+    format_srt, write_srt_file,
     parse_sxf_file, parse_sxf_binary,
-    parse_sxf_groups, write_groups_to_csv, groups_to_timesheet,
-    fill_keyframes, CsvEncoding,
+    parse_sxf_groups, parse_sxf_groups_with_warnings, SxfParseResult, write_sxf_binary, write_groups_to_csv, groups_to_timesheet,
+    fill_keyframes, CsvEncoding, CsvExportMode, DEFAULT_CSV_DELIMITER,
 };
@@ -4,4 +4,4 @@ pub mod timesheet;
 
 pub use keyframe::{Keyframe, TimeRemap};
 pub use layer::Layer;
-pub use timesheet::{TimeSheet, CellValue};
+pub use timesheet::{TimeSheet, TimeSheetBuilder, CellValue, LayerCells, LayerStats, LayerView};
@@ -1,4 +1,8 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::error::{Result, StsError};
 use serde::{Deserialize, Serialize};
+use crate::limits::{max_frames, MAX_LAYERS};
 
 /// 摄影表格式
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,11 +22,12 @@ pub struct TimeSheet {
     /// 图层名称
     pub layer_names: Vec<String>,
     
-    /// 单元格数据 [层][帧]
+    /// 单元格数据 [层][帧]（稀疏存储，空单元格不占内存）
     /// None = 空单元格
     /// Some(CellValue::Number(n)) = 数字
     /// Some(CellValue::Same) = "-" (和上一格相同)
-    pub cells: Vec<Vec<Option<CellValue>>>,
+    /// Some(CellValue::Labeled(n, c)) = 带后缀的编号，如 "12A"
+    pub cells: Vec<LayerCells>,
     
     /// 源文件宽度
     pub source_width: u32,
@@ -38,12 +43,237 @@ pub struct TimeSheet {
 }
 
 /// 单元格值
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CellValue {
     /// 数字
     Number(u32),
     /// 和上一格相同 (显示为 "-")
     Same,
+    /// 带字母后缀的编号，如 "12A"（后缀统一为大写）
+    Labeled(u32, char),
+}
+
+impl CellValue {
+    /// 解析原画编号："12" 或带一个字母后缀的 "12a"/"12A"，其他输入返回 None
+    pub fn parse_drawing(s: &str) -> Option<CellValue> {
+        let s = s.trim();
+        if let Ok(n) = s.parse::<u32>() {
+            return Some(CellValue::Number(n));
+        }
+        let suffix = s.chars().next_back().filter(char::is_ascii_alphabetic)?;
+        let n = s[..s.len() - 1].parse::<u32>().ok()?;
+        Some(CellValue::Labeled(n, suffix.to_ascii_uppercase()))
+    }
+
+    /// 编号 0 统一视为空白（STS 以 0 表示空格），各格式导入和编辑时都按此规则
+    #[inline]
+    pub fn non_blank(self) -> Option<CellValue> {
+        match self {
+            CellValue::Number(0) => None,
+            value => Some(value),
+        }
+    }
+
+    /// 编号的数字部分（Same 返回 None）
+    #[inline]
+    pub fn number(&self) -> Option<u32> {
+        match self {
+            CellValue::Number(n) | CellValue::Labeled(n, _) => Some(*n),
+            CellValue::Same => None,
+        }
+    }
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Number(n) => write!(f, "{}", n),
+            CellValue::Labeled(n, suffix) => write!(f, "{}{}", n, suffix),
+            CellValue::Same => f.write_str("-"),
+        }
+    }
+}
+
+/// 单列数据，只保存非空单元格
+///
+/// 序列化时与逐帧的 `Vec<Option<CellValue>>` 格式相同。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<Option<CellValue>>", into = "Vec<Option<CellValue>>")]
+pub struct LayerCells {
+    len: usize,
+    cells: BTreeMap<usize, CellValue>,
+}
+
+impl LayerCells {
+    /// 创建指定帧数的空列
+    pub fn new(len: usize) -> Self {
+        Self { len, cells: BTreeMap::new() }
+    }
+
+    /// 帧数（包括空单元格）
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 非空单元格数量
+    #[inline]
+    pub fn occupied(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// frame 及之后的非空单元格数量
+    pub fn occupied_from(&self, frame: usize) -> usize {
+        self.cells.range(frame..).count()
+    }
+
+    #[inline]
+    pub fn get(&self, frame: usize) -> Option<&CellValue> {
+        self.cells.get(&frame)
+    }
+
+    /// 设置单元格，超出帧数时自动扩展
+    pub fn set(&mut self, frame: usize, value: Option<CellValue>) {
+        if frame >= self.len {
+            self.len = frame + 1;
+        }
+        match value {
+            Some(v) => { self.cells.insert(frame, v); }
+            None => { self.cells.remove(&frame); }
+        }
+    }
+
+    /// 调整帧数，缩短时丢弃超出部分
+    pub fn resize(&mut self, len: usize) {
+        if len < self.len {
+            self.cells.split_off(&len);
+        }
+        self.len = len;
+    }
+
+    /// 在 at 处插入一帧，后续帧下移
+    pub fn insert(&mut self, at: usize, value: Option<CellValue>) {
+        let tail = self.cells.split_off(&at);
+        self.cells.extend(tail.into_iter().map(|(frame, v)| (frame + 1, v)));
+        if let Some(v) = value {
+            self.cells.insert(at, v);
+        }
+        self.len += 1;
+    }
+
+    /// 删除 at 处的帧，后续帧上移
+    pub fn remove(&mut self, at: usize) -> Option<CellValue> {
+        if at >= self.len {
+            return None;
+        }
+        let mut tail = self.cells.split_off(&at);
+        let removed = tail.remove(&at);
+        self.cells.extend(tail.into_iter().map(|(frame, v)| (frame - 1, v)));
+        self.len -= 1;
+        removed
+    }
+
+    /// 最近一个在 frame 之前的编号（Number 或 Labeled）
+    fn previous_drawing(&self, frame: usize) -> Option<CellValue> {
+        self.cells.range(..frame).rev().find_map(|(_, v)| match v {
+            CellValue::Same => None,
+            drawing => Some(*drawing),
+        })
+    }
+
+    /// 转换为逐帧数据
+    pub fn to_vec(&self) -> Vec<Option<CellValue>> {
+        let mut dense = vec![None; self.len];
+        for (&frame, &value) in &self.cells {
+            dense[frame] = Some(value);
+        }
+        dense
+    }
+}
+
+impl From<Vec<Option<CellValue>>> for LayerCells {
+    fn from(dense: Vec<Option<CellValue>>) -> Self {
+        let cells = dense.iter()
+            .enumerate()
+            .filter_map(|(frame, value)| value.map(|v| (frame, v)))
+            .collect();
+        Self { len: dense.len(), cells }
+    }
+}
+
+impl From<LayerCells> for Vec<Option<CellValue>> {
+    fn from(layer: LayerCells) -> Self {
+        layer.to_vec()
+    }
+}
+
+/// 单列统计信息
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LayerStats {
+    /// 不同原画/动画编号的数量
+    pub unique_drawings: usize,
+    /// 有值的帧数
+    pub total_exposed_frames: usize,
+    /// 平均每次出现持续的帧数（拍数）
+    pub avg_hold: f32,
+}
+
+/// 单列的只读视图，由 [`TimeSheet::layers`] 产出，不暴露内部存储
+#[derive(Debug, Clone, Copy)]
+pub struct LayerView<'a> {
+    timesheet: &'a TimeSheet,
+    index: usize,
+}
+
+impl<'a> LayerView<'a> {
+    /// 列序号（从 0 开始）
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// 单元格原值（"-" 保持为 `CellValue::Same`）
+    pub fn get(&self, frame: usize) -> Option<&'a CellValue> {
+        self.timesheet.get_cell(self.index, frame)
+    }
+
+    /// 逐帧实际编号，长度等于总帧数
+    pub fn actual_values(&self) -> impl Iterator<Item = Option<u32>> + 'a {
+        self.timesheet.actual_values(self.index)
+    }
+
+    /// 实际编号变化处的 (帧号, 实际编号)，同 [`TimeSheet::keyframes`]
+    pub fn keyframes(&self) -> impl Iterator<Item = (usize, Option<u32>)> + 'a {
+        self.timesheet.keyframes(self.index)
+    }
+
+    /// 带后缀的关键帧，同 [`TimeSheet::cell_keyframes`]
+    pub fn cell_keyframes(&self) -> impl Iterator<Item = (usize, Option<CellValue>)> + 'a {
+        self.timesheet.cell_keyframes(self.index)
+    }
+
+    /// 张数与平均拍数，同 [`TimeSheet::layer_stats`]
+    pub fn stats(&self) -> LayerStats {
+        self.timesheet.layer_stats(self.index)
+    }
+}
+
+/// 逐帧值中只保留变化处的 (帧号, 值)，第一帧总会产出
+fn change_points<T: PartialEq + Copy>(values: impl Iterator<Item = T>) -> impl Iterator<Item = (usize, T)> {
+    let mut prev_value: Option<T> = None;
+
+    values.enumerate().filter_map(move |(frame, value)| {
+        if prev_value == Some(value) {
+            None
+        } else {
+            prev_value = Some(value);
+            Some((frame, value))
+        }
+    })
 }
 
 impl TimeSheet {
@@ -54,7 +284,7 @@ impl TimeSheet {
             .collect();
 
         // 初始创建空表格，通过 ensure_frames 设置实际帧数
-        let cells = vec![vec![]; layer_count];
+        let cells = vec![LayerCells::default(); layer_count];
 
         Self {
             name,
@@ -90,43 +320,123 @@ impl TimeSheet {
     /// 获取单元格值
     #[inline(always)]
     pub fn get_cell(&self, layer: usize, frame: usize) -> Option<&CellValue> {
-        self.cells.get(layer)?.get(frame)?.as_ref()
+        self.cells.get(layer)?.get(frame)
     }
 
     /// 设置单元格值
     #[inline]
     pub fn set_cell(&mut self, layer: usize, frame: usize, value: Option<CellValue>) {
         if let Some(layer_cells) = self.cells.get_mut(layer) {
-            if frame >= layer_cells.len() {
-                // 限制最大自动扩展大小，防止意外的大量内存分配
-                const MAX_AUTO_EXTEND: usize = 10000;
-                if frame < MAX_AUTO_EXTEND {
-                    layer_cells.resize(frame + 1, None);
-                } else {
-                    // 超出限制，忽略这个设置操作
-                    return;
-                }
+            // 自动扩展不超过帧数上限，防止意外的大量内存分配
+            if frame >= layer_cells.len() && frame >= max_frames() {
+                // 超出限制，忽略这个设置操作
+                return;
             }
-            layer_cells[frame] = value;
+            layer_cells.set(frame, value);
         }
     }
 
-    /// 获取单元格的实际值
+    /// 获取单元格的实际值（带后缀的编号取数字部分）
     #[inline]
     pub fn get_actual_value(&self, layer: usize, frame: usize) -> Option<u32> {
-        let cell = self.get_cell(layer, frame)?;
-        
-        match cell {
-            CellValue::Number(n) => Some(*n),
-            CellValue::Same => {
-                // 向上查找最近的数字
-                for prev_frame in (0..frame).rev() {
-                    if let Some(CellValue::Number(n)) = self.get_cell(layer, prev_frame) {
-                        return Some(*n);
-                    }
+        self.get_actual_cell(layer, frame)?.number()
+    }
+
+    /// 获取单元格实际显示的编号（Same 解析为上方最近的 Number/Labeled，保留后缀）
+    #[inline]
+    pub fn get_actual_cell(&self, layer: usize, frame: usize) -> Option<CellValue> {
+        match self.get_cell(layer, frame)? {
+            // 向上查找最近的编号
+            CellValue::Same => self.cells[layer].previous_drawing(frame),
+            drawing => Some(*drawing),
+        }
+    }
+
+    /// 逐帧产出某一列的实际编号（Same 解析为上方最近的编号，保留后缀；空白为 None）
+    fn actual_cells(&self, layer: usize) -> impl Iterator<Item = Option<CellValue>> + '_ {
+        let cells = self.cells.get(layer);
+        let mut last_drawing: Option<CellValue> = None;
+
+        (0..self.total_frames()).map(move |frame| {
+            match cells.and_then(|c| c.get(frame)).copied() {
+                Some(CellValue::Same) => last_drawing,
+                Some(drawing) => {
+                    last_drawing = Some(drawing);
+                    Some(drawing)
                 }
-                None
+                None => None,
             }
+        })
+    }
+
+    /// 逐帧产出某一列的实际值（带后缀的编号取数字部分，空白为 None）
+    fn actual_values(&self, layer: usize) -> impl Iterator<Item = Option<u32>> + '_ {
+        self.actual_cells(layer).map(|value| value.and_then(|v| v.number()))
+    }
+
+    /// 遍历某一列的关键帧（仅在实际编号变化处产出 (帧号, 实际编号)）
+    ///
+    /// 第一帧总会产出；从有值变为空时产出 `None`。带后缀的编号取数字部分，
+    /// 需要区分 12 与 12A 时用 [`TimeSheet::cell_keyframes`]。
+    pub fn keyframes(&self, layer: usize) -> impl Iterator<Item = (usize, Option<u32>)> + '_ {
+        change_points(self.actual_values(layer))
+    }
+
+    /// 同 [`TimeSheet::keyframes`]，但产出带后缀的实际编号，12 → 12A 也是变化
+    pub fn cell_keyframes(&self, layer: usize) -> impl Iterator<Item = (usize, Option<CellValue>)> + '_ {
+        change_points(self.actual_cells(layer))
+    }
+
+    /// 按顺序遍历所有列的 (列名, 只读视图)
+    ///
+    /// ```
+    /// use sts_rust::{CellValue, TimeSheetBuilder};
+    ///
+    /// let ts = TimeSheetBuilder::new()
+    ///     .layer("A", &[Some(1), None, Some(2)])
+    ///     .layer("B", &[Some(3)])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// for (name, layer) in ts.layers() {
+    ///     let values: Vec<Option<u32>> = layer.actual_values().collect();
+    ///     println!("{}: {:?}", name, values);
+    /// }
+    /// let (_, a) = ts.layers().next().unwrap();
+    /// assert_eq!(
+    ///     a.keyframes().collect::<Vec<_>>(),
+    ///     vec![(0, Some(1)), (1, None), (2, Some(2))]
+    /// );
+    /// assert_eq!(a.cell_keyframes().next(), Some((0, Some(CellValue::Number(1)))));
+    /// ```
+    pub fn layers(&self) -> impl Iterator<Item = (&str, LayerView<'_>)> + '_ {
+        self.layer_names.iter()
+            .take(self.layer_count)
+            .enumerate()
+            .map(move |(index, name)| (name.as_str(), LayerView { timesheet: self, index }))
+    }
+
+    /// 统计某一列的张数与平均拍数
+    pub fn layer_stats(&self, layer: usize) -> LayerStats {
+        let total_frames = self.total_frames();
+        let keys: Vec<(usize, Option<CellValue>)> = self.cell_keyframes(layer).collect();
+
+        let mut drawings = std::collections::HashSet::new();
+        let mut exposures = 0usize;
+        let mut exposed_frames = 0usize;
+        for (i, &(frame, value)) in keys.iter().enumerate() {
+            if let Some(drawing) = value {
+                let end = keys.get(i + 1).map_or(total_frames, |k| k.0);
+                drawings.insert(drawing);
+                exposures += 1;
+                exposed_frames += end - frame;
+            }
+        }
+
+        LayerStats {
+            unique_drawings: drawings.len(),
+            total_exposed_frames: exposed_frames,
+            avg_hold: if exposures > 0 { exposed_frames as f32 / exposures as f32 } else { 0.0 },
         }
     }
 
@@ -139,6 +449,25 @@ impl TimeSheet {
         (page, frame_in_page)
     }
 
+    /// 帧数转换为 (秒, 余帧)，按 framerate 计算（如 24fps 时 24 帧 → (1, 0)）
+    #[inline]
+    pub fn frame_to_timecode(&self, frame: usize) -> (u32, u32) {
+        let framerate = self.framerate.max(1) as usize;
+        ((frame / framerate) as u32, (frame % framerate) as u32)
+    }
+
+    /// (秒, 余帧) 转换为帧数
+    #[inline]
+    pub fn timecode_to_frame(&self, sec: u32, frames: u32) -> usize {
+        sec as usize * self.framerate.max(1) as usize + frames as usize
+    }
+
+    /// 帧数格式化为 "秒+帧"，余帧补足两位（如 "3+08"）
+    pub fn format_timecode(&self, frame: usize) -> String {
+        let (sec, rem) = self.frame_to_timecode(frame);
+        format!("{}+{:02}", sec, rem)
+    }
+
     /// 获取总帧数
     #[inline]
     pub fn total_frames(&self) -> usize {
@@ -149,11 +478,23 @@ impl TimeSheet {
     pub fn ensure_frames(&mut self, frame_count: usize) {
         for layer_cells in &mut self.cells {
             if layer_cells.len() < frame_count {
-                layer_cells.resize(frame_count, None);
+                layer_cells.resize(frame_count);
             }
         }
     }
 
+    /// 将所有列调整为指定帧数：不足时补空，超出的部分截断
+    pub fn set_total_frames(&mut self, frame_count: usize) {
+        for layer_cells in &mut self.cells {
+            layer_cells.resize(frame_count);
+        }
+    }
+
+    /// 所有列中 frame 及之后的非空单元格数量（缩短时会被丢弃的内容）
+    pub fn occupied_from(&self, frame: usize) -> usize {
+        self.cells.iter().map(|layer_cells| layer_cells.occupied_from(frame)).sum()
+    }
+
     /// 在指定位置插入一列
     pub fn insert_layer(&mut self, index: usize) {
         if index > self.layer_count {
@@ -167,7 +508,7 @@ impl TimeSheet {
         let frame_count = self.total_frames();
 
         // 插入空列数据
-        self.cells.insert(index, vec![None; frame_count]);
+        self.cells.insert(index, LayerCells::new(frame_count));
         self.layer_names.insert(index, new_name);
         self.layer_count += 1;
     }
@@ -181,7 +522,145 @@ impl TimeSheet {
         let name = self.layer_names.remove(index);
         let cells = self.cells.remove(index);
         self.layer_count -= 1;
-        Some((name, cells))
+        Some((name, cells.into()))
+    }
+
+    /// 保存为 JSON（无损，保留 "-"、帧率和源尺寸）
+    pub fn save_json(&self, path: &str) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| StsError::json("Failed to serialize timesheet to JSON", e))?;
+        std::fs::write(path, json)
+            .map_err(|e| StsError::io(format!("Failed to write JSON file: {}", path), e))?;
+        Ok(())
+    }
+
+    /// 从 JSON 加载
+    pub fn load_json(path: &str) -> Result<TimeSheet> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| StsError::io(format!("Failed to read JSON file: {}", path), e))?;
+        let timesheet: TimeSheet = serde_json::from_str(&content)
+            .map_err(|e| StsError::json("Failed to parse timesheet JSON", e))?;
+
+        if timesheet.layer_count != timesheet.cells.len()
+            || timesheet.layer_count != timesheet.layer_names.len()
+        {
+            return Err(StsError::Decode(format!(
+                "Inconsistent layer data in JSON: layer_count {}, cells {}, names {}",
+                timesheet.layer_count,
+                timesheet.cells.len(),
+                timesheet.layer_names.len()
+            )));
+        }
+        if timesheet.layer_count > MAX_LAYERS {
+            return Err(StsError::TooManyLayers { found: timesheet.layer_count, max: MAX_LAYERS });
+        }
+        if let Some(found) = timesheet.cells.iter().map(|layer| layer.len()).max().filter(|&n| n > max_frames()) {
+            return Err(StsError::TooManyFrames { found, max: max_frames() });
+        }
+        if timesheet.frames_per_page == 0 {
+            return Err(StsError::Decode("Invalid frames per page in JSON file: 0".to_string()));
+        }
+
+        Ok(timesheet)
+    }
+
+    /// 按每页帧数拆分为多张表，命名为 "{name}_p{n}"
+    ///
+    /// 最后一页可能不足一页；页首的 "-" 转换为实际数字，保证每页独立可读。
+    pub fn split_by_page(&self) -> Vec<TimeSheet> {
+        let total_frames = self.total_frames();
+        let page_len = self.frames_per_page.max(1) as usize;
+        if self.layer_count == 0 {
+            return Vec::new();
+        }
+
+        (0..total_frames.div_ceil(page_len))
+            .map(|page| {
+                let start = page * page_len;
+                let end = (start + page_len).min(total_frames);
+
+                let mut sheet = self.extract_range(0, start, self.layer_count - 1, end - 1);
+                sheet.name = format!("{}_p{}", self.name, page + 1);
+                sheet
+            })
+            .collect()
+    }
+
+    /// 取出 min_layer..=max_layer 列、min_frame..=max_frame 帧为一张新表（帧号从 0 开始）
+    ///
+    /// 保留列名和其他元数据；首帧的 "-" 转换为实际数字。范围需在表内。
+    pub fn extract_range(&self, min_layer: usize, min_frame: usize, max_layer: usize, max_frame: usize) -> TimeSheet {
+        let mut sheet = TimeSheet::new(self.name.clone(), self.framerate, 0, self.frames_per_page);
+        sheet.source_width = self.source_width;
+        sheet.source_height = self.source_height;
+        sheet.source_pixel_aspect_ratio = self.source_pixel_aspect_ratio;
+        sheet.comp_pixel_aspect_ratio = self.comp_pixel_aspect_ratio;
+        sheet.layer_names = self.layer_names[min_layer..=max_layer].to_vec();
+        sheet.layer_count = max_layer - min_layer + 1;
+
+        // 只复制范围内的非空单元格
+        sheet.cells = self.cells[min_layer..=max_layer].iter()
+            .map(|layer_cells| {
+                let mut range_cells = LayerCells::new(max_frame - min_frame + 1);
+                for (&frame, &value) in layer_cells.cells.range(min_frame..=max_frame) {
+                    range_cells.set(frame - min_frame, Some(value));
+                }
+                // 首帧的 "-" 转换为上方最近的编号
+                if layer_cells.get(min_frame) == Some(&CellValue::Same) {
+                    range_cells.set(0, layer_cells.previous_drawing(min_frame));
+                }
+                range_cells
+            })
+            .collect();
+        sheet
+    }
+
+    /// 移动列：把 from 列移动到 to 位置（移动后的索引）
+    pub fn move_layer(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.layer_count || to >= self.layer_count || from == to {
+            return false;
+        }
+
+        let cells = self.cells.remove(from);
+        self.cells.insert(to, cells);
+        let name = self.layer_names.remove(from);
+        self.layer_names.insert(to, name);
+        true
+    }
+
+    /// 在所有列的指定帧位置插入一帧，后续帧下移
+    /// 插入的格子延续上一帧（有值时为 "-"，否则为空）
+    pub fn insert_frame(&mut self, at: usize) -> bool {
+        if at > self.total_frames() {
+            return false;
+        }
+
+        for layer in 0..self.layer_count {
+            let held = if at > 0 && self.get_actual_value(layer, at - 1).is_some() {
+                Some(CellValue::Same)
+            } else {
+                None
+            };
+            let layer_cells = &mut self.cells[layer];
+            if at <= layer_cells.len() {
+                layer_cells.insert(at, held);
+            }
+        }
+        true
+    }
+
+    /// 删除所有列的指定帧，后续帧上移
+    pub fn delete_frame(&mut self, at: usize) -> bool {
+        if at >= self.total_frames() {
+            return false;
+        }
+
+        for layer_cells in &mut self.cells {
+            if at < layer_cells.len() {
+                layer_cells.remove(at);
+            }
+        }
+        true
     }
 }
 
@@ -191,9 +670,149 @@ impl Default for TimeSheet {
     }
 }
 
+/// 逐列构建 TimeSheet，列数和总帧数自动计算
+///
+/// 较短的列在末尾补空；`frames()` 可指定最少帧数。
+///
+/// ```
+/// use sts_rust::{CellValue, TimeSheetBuilder};
+///
+/// let ts = TimeSheetBuilder::new()
+///     .name("cut01")
+///     .framerate(24)
+///     .layer("A", &[Some(1), None, Some(2)])
+///     .layer("B", &[Some(3)])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(ts.layer_count, 2);
+/// assert_eq!(ts.total_frames(), 3);
+/// assert_eq!(ts.layer_names, vec!["A", "B"]);
+/// assert_eq!(ts.get_cell(0, 2), Some(&CellValue::Number(2)));
+/// assert_eq!(ts.get_cell(1, 2), None);
+/// ```
+///
+/// 需要 "-" 或带后缀的编号时使用 `layer_cells`：
+///
+/// ```
+/// use sts_rust::{CellValue, TimeSheetBuilder};
+///
+/// let ts = TimeSheetBuilder::new()
+///     .frames(24)
+///     .layer_cells("A", &[Some(CellValue::Number(1)), Some(CellValue::Same)])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(ts.total_frames(), 24);
+/// assert_eq!(ts.get_actual_value(0, 1), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeSheetBuilder {
+    name: String,
+    framerate: u32,
+    frames_per_page: u32,
+    min_frames: usize,
+    source_size: Option<(u32, u32)>,
+    layers: Vec<(String, Vec<Option<CellValue>>)>,
+}
+
+impl Default for TimeSheetBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSheetBuilder {
+    /// 默认值与 `TimeSheet::default()` 相同：名称 "sheet1"、24fps、每页 144 帧
+    pub fn new() -> Self {
+        Self {
+            name: "sheet1".to_string(),
+            framerate: 24,
+            frames_per_page: 144,
+            min_frames: 0,
+            source_size: None,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn framerate(mut self, framerate: u32) -> Self {
+        self.framerate = framerate;
+        self
+    }
+
+    pub fn frames_per_page(mut self, frames_per_page: u32) -> Self {
+        self.frames_per_page = frames_per_page;
+        self
+    }
+
+    /// 最少帧数（列数据较短时补空）
+    pub fn frames(mut self, frame_count: usize) -> Self {
+        self.min_frames = frame_count;
+        self
+    }
+
+    pub fn source_size(mut self, width: u32, height: u32) -> Self {
+        self.source_size = Some((width, height));
+        self
+    }
+
+    /// 添加一列数字，None 为空格
+    pub fn layer(self, name: impl Into<String>, values: &[Option<u32>]) -> Self {
+        let cells: Vec<Option<CellValue>> = values.iter().map(|v| v.map(CellValue::Number)).collect();
+        self.layer_cells(name, &cells)
+    }
+
+    /// 添加一列任意单元格值
+    pub fn layer_cells(mut self, name: impl Into<String>, cells: &[Option<CellValue>]) -> Self {
+        self.layers.push((name.into(), cells.to_vec()));
+        self
+    }
+
+    /// 检查限制并生成 TimeSheet
+    pub fn build(self) -> Result<TimeSheet> {
+        if self.framerate == 0 {
+            return Err(StsError::InvalidData("Framerate must be greater than 0".to_string()));
+        }
+        if self.frames_per_page == 0 {
+            return Err(StsError::InvalidData("Frames per page must be greater than 0".to_string()));
+        }
+        if self.layers.len() > MAX_LAYERS {
+            return Err(StsError::TooManyLayers { found: self.layers.len(), max: MAX_LAYERS });
+        }
+        let frame_count = self.layers.iter()
+            .map(|(_, cells)| cells.len())
+            .max()
+            .unwrap_or(0)
+            .max(self.min_frames);
+        if frame_count > max_frames() {
+            return Err(StsError::TooManyFrames { found: frame_count, max: max_frames() });
+        }
+
+        let mut timesheet = TimeSheet::new(self.name, self.framerate, 0, self.frames_per_page);
+        if let Some((width, height)) = self.source_size {
+            timesheet.source_width = width;
+            timesheet.source_height = height;
+        }
+        timesheet.layer_count = self.layers.len();
+        for (name, cells) in self.layers {
+            let mut layer_cells = LayerCells::from(cells);
+            layer_cells.resize(frame_count);
+            timesheet.layer_names.push(name);
+            timesheet.cells.push(layer_cells);
+        }
+        Ok(timesheet)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::limits::MAX_FRAMES;
 
     #[test]
     fn test_column_name() {
@@ -235,4 +854,317 @@ mod tests {
         assert_eq!(ts.get_actual_value(0, 2), Some(2));
         assert_eq!(ts.get_actual_value(0, 3), Some(2)); // "-" = 2
     }
+
+    #[test]
+    fn test_insert_and_delete_frame() {
+        let mut ts = TimeSheet::new("test".to_string(), 24, 2, 144);
+        ts.ensure_frames(3);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Number(2)));
+        ts.set_cell(1, 2, Some(CellValue::Number(5)));
+
+        assert!(ts.insert_frame(1));
+        assert_eq!(ts.total_frames(), 4);
+        assert_eq!(ts.get_cell(0, 1), Some(&CellValue::Same));
+        assert_eq!(ts.get_actual_value(0, 1), Some(1));
+        assert_eq!(ts.get_cell(0, 2), Some(&CellValue::Number(2)));
+        assert_eq!(ts.get_cell(1, 1), None);
+        assert_eq!(ts.get_cell(1, 3), Some(&CellValue::Number(5)));
+
+        assert!(ts.delete_frame(0));
+        assert_eq!(ts.total_frames(), 3);
+        assert_eq!(ts.get_cell(0, 1), Some(&CellValue::Number(2)));
+        assert_eq!(ts.get_cell(1, 2), Some(&CellValue::Number(5)));
+
+        // 越界保护
+        assert!(!ts.insert_frame(4));
+        assert!(ts.insert_frame(3));
+        assert!(!ts.delete_frame(4));
+    }
+
+    #[test]
+    fn test_move_layer() {
+        let mut ts = TimeSheet::new("test".to_string(), 24, 3, 144);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(2, 0, Some(CellValue::Number(3)));
+
+        assert!(ts.move_layer(0, 2));
+        assert_eq!(ts.layer_names, vec!["B", "C", "A"]);
+        assert_eq!(ts.get_cell(2, 0), Some(&CellValue::Number(1)));
+        assert_eq!(ts.get_cell(1, 0), Some(&CellValue::Number(3)));
+
+        assert!(!ts.move_layer(1, 1));
+        assert!(!ts.move_layer(0, 3));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut ts = TimeSheet::new("json".to_string(), 30, 2, 72);
+        ts.source_width = 1920;
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(1, 3, Some(CellValue::Number(4)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+        let path = path.to_str().unwrap();
+        ts.save_json(path).unwrap();
+
+        let loaded = TimeSheet::load_json(path).unwrap();
+        assert_eq!(loaded.framerate, 30);
+        assert_eq!(loaded.frames_per_page, 72);
+        assert_eq!(loaded.source_width, 1920);
+        assert_eq!(loaded.layer_names, ts.layer_names);
+        assert_eq!(loaded.cells, ts.cells);
+    }
+
+    #[test]
+    fn test_json_rejects_inconsistent_layers() {
+        let mut ts = TimeSheet::new("json".to_string(), 24, 2, 144);
+        ts.layer_count = 3;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.json");
+        let path = path.to_str().unwrap();
+        ts.save_json(path).unwrap();
+
+        assert!(TimeSheet::load_json(path).is_err());
+    }
+
+    #[test]
+    fn test_keyframes() {
+        let mut ts = TimeSheet::new("test".to_string(), 24, 2, 144);
+        ts.ensure_frames(7);
+        ts.set_cell(0, 1, Some(CellValue::Number(1)));
+        ts.set_cell(0, 2, Some(CellValue::Same));
+        ts.set_cell(0, 3, Some(CellValue::Number(1)));
+        ts.set_cell(0, 4, Some(CellValue::Number(2)));
+        ts.set_cell(0, 6, Some(CellValue::Same));
+
+        let keys: Vec<_> = ts.keyframes(0).collect();
+        assert_eq!(keys, vec![(0, None), (1, Some(1)), (4, Some(2)), (5, None), (6, Some(2))]);
+
+        // 空列只产出第一帧
+        assert_eq!(ts.keyframes(1).collect::<Vec<_>>(), vec![(0, None)]);
+        // 与 get_actual_value / get_actual_cell 一致
+        for (frame, value) in ts.keyframes(0) {
+            assert_eq!(ts.get_actual_value(0, frame), value);
+        }
+        for (frame, value) in ts.cell_keyframes(0) {
+            assert_eq!(ts.get_actual_cell(0, frame), value);
+        }
+
+        // 带后缀的编号是不同的原画：12 → 12A 是变化，12A → "-" 不是
+        let n = |n| Some(CellValue::Number(n));
+        ts.set_cell(1, 0, Some(CellValue::Number(12)));
+        ts.set_cell(1, 1, Some(CellValue::Labeled(12, 'A')));
+        ts.set_cell(1, 2, Some(CellValue::Same));
+        ts.set_cell(1, 3, Some(CellValue::Number(12)));
+        assert_eq!(ts.cell_keyframes(1).take(3).collect::<Vec<_>>(), vec![
+            (0, n(12)), (1, Some(CellValue::Labeled(12, 'A'))), (3, n(12)),
+        ]);
+        // 只看编号时 12 与 12A 相同
+        assert_eq!(ts.keyframes(1).take(2).collect::<Vec<_>>(), vec![(0, Some(12)), (4, None)]);
+    }
+
+    #[test]
+    fn test_layer_stats() {
+        let mut ts = TimeSheet::new("test".to_string(), 24, 2, 144);
+        ts.ensure_frames(8);
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(0, 2, Some(CellValue::Number(2)));
+        ts.set_cell(0, 4, Some(CellValue::Number(1)));
+        ts.set_cell(0, 5, Some(CellValue::Same));
+
+        // 1 1 2 空 1 1 空 空 -> 3 次出现共 5 帧
+        let stats = ts.layer_stats(0);
+        assert_eq!(stats.unique_drawings, 2);
+        assert_eq!(stats.total_exposed_frames, 5);
+        assert!((stats.avg_hold - 5.0 / 3.0).abs() < 1e-6);
+
+        assert_eq!(ts.layer_stats(1), LayerStats::default());
+
+        // 12 和 12A 分别计数
+        ts.set_cell(1, 0, Some(CellValue::Number(12)));
+        ts.set_cell(1, 2, Some(CellValue::Labeled(12, 'A')));
+        let stats = ts.layer_stats(1);
+        assert_eq!(stats.unique_drawings, 2);
+        assert_eq!(stats.total_exposed_frames, 2);
+    }
+
+    #[test]
+    fn test_layer_cells_sparse_ops() {
+        let mut layer = LayerCells::from(vec![Some(CellValue::Number(1)), None, Some(CellValue::Same), None]);
+        assert_eq!(layer.len(), 4);
+        assert_eq!(layer.occupied(), 2);
+
+        layer.insert(1, Some(CellValue::Number(7)));
+        assert_eq!(layer.to_vec(), vec![
+            Some(CellValue::Number(1)), Some(CellValue::Number(7)), None, Some(CellValue::Same), None,
+        ]);
+
+        assert_eq!(layer.remove(0), Some(CellValue::Number(1)));
+        assert_eq!(layer.remove(10), None);
+        assert_eq!(layer.to_vec(), vec![Some(CellValue::Number(7)), None, Some(CellValue::Same), None]);
+
+        layer.resize(2);
+        assert_eq!(layer.occupied(), 1);
+        layer.set(1, None);
+        assert_eq!(layer.to_vec(), vec![Some(CellValue::Number(7)), None]);
+
+        // 序列化格式与逐帧数组一致
+        let json = serde_json::to_string(&layer).unwrap();
+        assert_eq!(json, r#"[{"Number":7},null]"#);
+        assert_eq!(serde_json::from_str::<LayerCells>(&json).unwrap(), layer);
+    }
+
+    #[test]
+    fn test_large_sparse_sheet() {
+        // 500 列 x 100k 帧，几乎全空；密集存储需要约 400MB
+        let mut ts = TimeSheet::new("big".to_string(), 24, 500, 144);
+        ts.ensure_frames(MAX_FRAMES);
+        assert_eq!(ts.total_frames(), MAX_FRAMES);
+
+        ts.set_cell(0, 0, Some(CellValue::Number(1)));
+        ts.set_cell(0, 1, Some(CellValue::Same));
+        ts.set_cell(499, MAX_FRAMES - 2, Some(CellValue::Number(3)));
+        ts.set_cell(499, MAX_FRAMES - 1, Some(CellValue::Same));
+
+        let occupied: usize = ts.cells.iter().map(|layer| layer.occupied()).sum();
+        assert_eq!(occupied, 4);
+        assert_eq!(ts.get_actual_value(0, 1), Some(1));
+        assert_eq!(ts.get_actual_value(499, MAX_FRAMES - 1), Some(3));
+        assert_eq!(ts.get_cell(250, 50_000), None);
+
+        assert!(ts.insert_frame(0));
+        assert_eq!(ts.total_frames(), MAX_FRAMES + 1);
+        assert_eq!(ts.get_cell(499, MAX_FRAMES), Some(&CellValue::Same));
+        assert!(ts.delete_frame(0));
+        assert_eq!(ts.get_cell(0, 0), Some(&CellValue::Number(1)));
+    }
+
+    #[test]
+    fn test_split_by_page() {
+        let mut ts = TimeSheet::new("cut".to_string(), 24, 2, 4);
+        ts.ensure_frames(10);
+        ts.set_cell(0, 3, Some(CellValue::Number(2)));
+        ts.set_cell(0, 4, Some(CellValue::Same));
+        ts.set_cell(1, 9, Some(CellValue::Number(7)));
+        ts.layer_names[1] = "BG".to_string();
+
+        let pages = ts.split_by_page();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0].name, "cut_p1");
+        assert_eq!(pages[2].name, "cut_p3");
+        assert_eq!(pages.iter().map(|p| p.total_frames()).collect::<Vec<_>>(), vec![4, 4, 2]);
+        assert_eq!(pages[1].layer_names, ts.layer_names);
+        assert_eq!(pages[1].get_cell(0, 0), Some(&CellValue::Number(2)));
+        assert_eq!(pages[2].get_cell(1, 1), Some(&CellValue::Number(7)));
+
+        // 正好整页时不产生空的尾页
+        ts.delete_frame(9);
+        ts.delete_frame(8);
+        assert_eq!(ts.split_by_page().len(), 2);
+    }
+
+    #[test]
+    fn test_extract_range() {
+        let mut ts = TimeSheet::new("cut".to_string(), 24, 3, 144);
+        ts.ensure_frames(10);
+        ts.set_cell(1, 2, Some(CellValue::Number(4)));
+        ts.set_cell(1, 3, Some(CellValue::Same));
+        ts.set_cell(2, 5, Some(CellValue::Number(6)));
+        ts.layer_names[2] = "BG".to_string();
+        ts.source_width = 1920;
+        ts.comp_pixel_aspect_ratio = 0.9;
+
+        let sheet = ts.extract_range(1, 3, 2, 6);
+        assert_eq!(sheet.layer_count, 2);
+        assert_eq!(sheet.layer_names, vec!["B".to_string(), "BG".to_string()]);
+        assert_eq!(sheet.total_frames(), 4);
+        assert_eq!(sheet.framerate, 24);
+        assert_eq!(sheet.source_width, 1920);
+        assert_eq!(sheet.comp_pixel_aspect_ratio, 0.9);
+        assert_eq!(sheet.get_cell(0, 0), Some(&CellValue::Number(4)));
+        assert_eq!(sheet.get_cell(1, 2), Some(&CellValue::Number(6)));
+        assert_eq!(sheet.get_cell(1, 3), None);
+    }
+
+    #[test]
+    fn test_labeled_drawings() {
+        assert_eq!(CellValue::parse_drawing("12"), Some(CellValue::Number(12)));
+        assert_eq!(CellValue::parse_drawing(" 12a "), Some(CellValue::Labeled(12, 'A')));
+        assert_eq!(CellValue::parse_drawing("12AB"), None);
+        assert_eq!(CellValue::parse_drawing("A"), None);
+        assert_eq!(CellValue::parse_drawing("-"), None);
+        assert_eq!(CellValue::Labeled(12, 'B').to_string(), "12B");
+
+        let mut ts = TimeSheet::new("cut".to_string(), 24, 1, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(0, 0, Some(CellValue::Number(12)));
+        ts.set_cell(0, 1, Some(CellValue::Labeled(12, 'A')));
+        ts.set_cell(0, 2, Some(CellValue::Same));
+        assert_eq!(ts.get_actual_cell(0, 2), Some(CellValue::Labeled(12, 'A')));
+        assert_eq!(ts.get_actual_value(0, 2), Some(12));
+        assert_eq!(ts.get_actual_cell(0, 3), None);
+    }
+
+    #[test]
+    fn test_builder_limits() {
+        let ts = TimeSheetBuilder::new().frames_per_page(12).build().unwrap();
+        assert_eq!((ts.layer_count, ts.total_frames(), ts.frames_per_page), (0, 0, 12));
+
+        assert!(matches!(TimeSheetBuilder::new().framerate(0).build(), Err(StsError::InvalidData(_))));
+        assert!(matches!(
+            TimeSheetBuilder::new().layer("A", &[]).frames(MAX_FRAMES + 1).build(),
+            Err(StsError::TooManyFrames { .. })
+        ));
+    }
+
+    #[test]
+    fn test_timecode() {
+        let ts = TimeSheet::new("tc".to_string(), 24, 1, 144);
+        assert_eq!(ts.frame_to_timecode(0), (0, 0));
+        assert_eq!(ts.frame_to_timecode(23), (0, 23));
+        assert_eq!(ts.frame_to_timecode(24), (1, 0));
+        assert_eq!(ts.frame_to_timecode(58), (2, 10));
+        assert_eq!(ts.timecode_to_frame(2, 10), 58);
+        assert_eq!(ts.format_timecode(56), "2+08");
+
+        let ts = TimeSheet::new("tc".to_string(), 30, 1, 144);
+        assert_eq!(ts.frame_to_timecode(24), (0, 24));
+        for frame in [0, 29, 30, 1234] {
+            let (sec, rem) = ts.frame_to_timecode(frame);
+            assert_eq!(ts.timecode_to_frame(sec, rem), frame);
+        }
+    }
+
+    #[test]
+    fn test_layer_views() {
+        let ts = TimeSheetBuilder::new()
+            .layer_cells("A", &[Some(CellValue::Number(1)), Some(CellValue::Same), None, Some(CellValue::Same)])
+            .layer_cells("B", &[None, Some(CellValue::Labeled(2, 'A')), Some(CellValue::Number(2))])
+            .build()
+            .unwrap();
+
+        let layers: Vec<(&str, LayerView)> = ts.layers().collect();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[1].0, "B");
+        assert_eq!(layers[1].1.index(), 1);
+        assert_eq!(layers[1].1.get(1), Some(&CellValue::Labeled(2, 'A')));
+
+        for (layer, (_, view)) in layers.iter().enumerate() {
+            let expected: Vec<Option<u32>> = (0..ts.total_frames()).map(|frame| ts.get_actual_value(layer, frame)).collect();
+            assert_eq!(view.actual_values().collect::<Vec<_>>(), expected);
+            assert_eq!(view.keyframes().collect::<Vec<_>>(), ts.keyframes(layer).collect::<Vec<_>>());
+            assert_eq!(view.cell_keyframes().collect::<Vec<_>>(), ts.cell_keyframes(layer).collect::<Vec<_>>());
+        }
+        assert_eq!(layers[0].1.actual_values().collect::<Vec<_>>(), vec![Some(1), Some(1), None, Some(1)]);
+        assert_eq!(layers[1].1.cell_keyframes().collect::<Vec<_>>(), vec![
+            (0, None), (1, Some(CellValue::Labeled(2, 'A'))), (2, Some(CellValue::Number(2))), (3, None),
+        ]);
+        assert_eq!(layers[1].1.keyframes().collect::<Vec<_>>(), vec![(0, None), (1, Some(2)), (3, None)]);
+        assert_eq!(layers[1].1.stats().unique_drawings, 2);
+    }
 }
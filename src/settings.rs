@@ -1,28 +1,39 @@
 //! Settings module - handles application settings storage
 //! - Windows: uses registry
-//! - macOS/Linux: uses config file (JSON)
+//! - macOS/Linux (or Windows without `winreg`): uses config file (JSON)
 
 #[cfg(all(windows, feature = "winreg"))]
 use winreg::enums::*;
 #[cfg(all(windows, feature = "winreg"))]
 use winreg::RegKey;
 
-#[cfg(all(not(windows), feature = "dirs"))]
+#[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
 use std::fs;
-#[cfg(all(not(windows), feature = "dirs"))]
-use std::path::PathBuf;
+#[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
+use std::path::{Path, PathBuf};
 
 // Re-export CsvEncoding from library
-pub use sts_rust::CsvEncoding;
+pub use sts_rust::{CsvEncoding, CsvExportMode};
+use sts_rust::DEFAULT_CSV_DELIMITER;
 
 #[cfg(all(windows, feature = "winreg"))]
 const REGISTRY_KEY: &str = r"Software\STS-Rust";
 
-#[cfg(all(not(windows), feature = "dirs"))]
+#[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
 const CONFIG_FILE_NAME: &str = "settings.json";
-#[cfg(all(not(windows), feature = "dirs"))]
+#[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
 const APP_NAME: &str = "sts-rust";
 
+/// Grid zoom range in percent
+pub const MIN_GRID_ZOOM_PERCENT: u32 = 50;
+pub const MAX_GRID_ZOOM_PERCENT: u32 = 300;
+
+/// Number of per-file window layouts kept (most recently used first)
+pub const MAX_WINDOW_LAYOUTS: usize = 64;
+
+/// Document window layout: [x, y, content width, content height]
+pub type WindowLayout = [f32; 4];
+
 /// Theme mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ThemeMode {
@@ -41,7 +52,7 @@ impl ThemeMode {
         }
     }
 
-    pub fn from_str(s: &str) -> Self {
+    pub fn from_label(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "light" => ThemeMode::Light,
             "dark" => ThemeMode::Dark,
@@ -70,7 +81,7 @@ impl AeKeyframeVersion {
         }
     }
 
-    pub fn from_str(s: &str) -> Self {
+    pub fn from_label(s: &str) -> Self {
         match s {
             "6.0" => AeKeyframeVersion::V6,
             "7.0" => AeKeyframeVersion::V7,
@@ -104,12 +115,24 @@ pub struct AppSettings {
     // CSV export settings
     pub csv_header_name: String,
     pub csv_encoding: CsvEncoding,
+    pub csv_delimiter: u8,
+    pub csv_export_mode: CsvExportMode,
     // Auto-save settings
     pub auto_save_enabled: bool,
     // Theme settings
     pub theme_mode: ThemeMode,
     // AE keyframe settings
     pub ae_keyframe_version: AeKeyframeVersion,
+    // New document defaults (last used values)
+    pub new_framerate: u32,
+    pub new_layer_count: usize,
+    pub new_frames_per_page: u32,
+    pub new_seconds: u32,
+    pub new_frames: u32,
+    // Grid zoom in percent (cell width/height and font scale together)
+    pub grid_zoom_percent: u32,
+    // Document window layouts keyed by file path, most recently used first
+    pub window_layouts: Vec<(String, WindowLayout)>,
 }
 
 impl Default for AppSettings {
@@ -117,14 +140,71 @@ impl Default for AppSettings {
         Self {
             csv_header_name: "动画".to_string(),
             csv_encoding: CsvEncoding::Gb2312,
+            csv_delimiter: DEFAULT_CSV_DELIMITER,
+            csv_export_mode: CsvExportMode::KeyframesOnly,
             auto_save_enabled: false,
             theme_mode: ThemeMode::System,
             ae_keyframe_version: AeKeyframeVersion::V9,
+            new_framerate: 24,
+            new_layer_count: 12,
+            new_frames_per_page: 144,
+            new_seconds: 6,
+            new_frames: 0,
+            grid_zoom_percent: 100,
+            window_layouts: Vec::new(),
         }
     }
 }
 
 impl AppSettings {
+    /// Grid zoom factor (1.0 = 100%)
+    pub fn grid_zoom(&self) -> f32 {
+        self.grid_zoom_percent as f32 / 100.0
+    }
+
+    /// Saved window layout for a file, if any
+    pub fn window_layout(&self, path: &str) -> Option<WindowLayout> {
+        self.window_layouts.iter()
+            .find(|(p, _)| p == path)
+            .map(|&(_, layout)| layout)
+    }
+
+    /// Remember a file's window layout, moving it to the front and dropping the oldest
+    pub fn remember_window_layout(&mut self, path: &str, layout: WindowLayout) {
+        if !layout.iter().all(|v| v.is_finite()) {
+            return;
+        }
+        self.window_layouts.retain(|(p, _)| p != path);
+        self.window_layouts.insert(0, (path.to_string(), layout));
+        self.window_layouts.truncate(MAX_WINDOW_LAYOUTS);
+    }
+
+    /// Serialize window layouts as `[{"path": ..., "rect": [x, y, w, h]}, ...]`
+    #[cfg(any(all(windows, feature = "winreg"), feature = "dirs"))]
+    fn window_layouts_json(&self) -> serde_json::Value {
+        self.window_layouts.iter()
+            .map(|(path, rect)| serde_json::json!({ "path": path, "rect": rect }))
+            .collect()
+    }
+
+    /// Parse window layouts written by `window_layouts_json`; invalid entries are skipped
+    #[cfg(any(all(windows, feature = "winreg"), feature = "dirs"))]
+    fn parse_window_layouts(json: &serde_json::Value) -> Vec<(String, WindowLayout)> {
+        let Some(entries) = json.as_array() else {
+            return Vec::new();
+        };
+        entries.iter()
+            .filter_map(|entry| {
+                let path = entry.get("path")?.as_str()?;
+                let rect = entry.get("rect")?.as_array()?;
+                let values: Vec<f32> = rect.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+                let layout: WindowLayout = values.try_into().ok()?;
+                layout.iter().all(|v| v.is_finite()).then(|| (path.to_string(), layout))
+            })
+            .take(MAX_WINDOW_LAYOUTS)
+            .collect()
+    }
+
     // ========== Windows: Registry-based storage ==========
 
     /// Load settings from Windows registry
@@ -137,16 +217,51 @@ impl AppSettings {
                 settings.csv_header_name = header_name;
             }
             if let Ok(encoding) = hkcu.get_value::<String, _>("CsvEncoding") {
-                settings.csv_encoding = CsvEncoding::from_str(&encoding);
+                settings.csv_encoding = CsvEncoding::from_label(&encoding);
+            }
+            if let Ok(delimiter) = hkcu.get_value::<u32, _>("CsvDelimiter") {
+                settings.csv_delimiter = delimiter as u8;
+            }
+            if let Ok(mode) = hkcu.get_value::<String, _>("CsvExportMode") {
+                settings.csv_export_mode = CsvExportMode::from_label(&mode);
             }
             if let Ok(auto_save) = hkcu.get_value::<u32, _>("AutoSaveEnabled") {
                 settings.auto_save_enabled = auto_save != 0;
             }
             if let Ok(theme) = hkcu.get_value::<String, _>("ThemeMode") {
-                settings.theme_mode = ThemeMode::from_str(&theme);
+                settings.theme_mode = ThemeMode::from_label(&theme);
             }
             if let Ok(ae_version) = hkcu.get_value::<String, _>("AeKeyframeVersion") {
-                settings.ae_keyframe_version = AeKeyframeVersion::from_str(&ae_version);
+                settings.ae_keyframe_version = AeKeyframeVersion::from_label(&ae_version);
+            }
+            if let Ok(framerate) = hkcu.get_value::<u32, _>("NewFramerate") {
+                if framerate > 0 {
+                    settings.new_framerate = framerate;
+                }
+            }
+            if let Ok(layer_count) = hkcu.get_value::<u32, _>("NewLayerCount") {
+                if layer_count > 0 {
+                    settings.new_layer_count = layer_count as usize;
+                }
+            }
+            if let Ok(frames_per_page) = hkcu.get_value::<u32, _>("NewFramesPerPage") {
+                if frames_per_page > 0 {
+                    settings.new_frames_per_page = frames_per_page;
+                }
+            }
+            if let Ok(seconds) = hkcu.get_value::<u32, _>("NewSeconds") {
+                settings.new_seconds = seconds;
+            }
+            if let Ok(frames) = hkcu.get_value::<u32, _>("NewFrames") {
+                settings.new_frames = frames;
+            }
+            if let Ok(zoom) = hkcu.get_value::<u32, _>("GridZoomPercent") {
+                settings.grid_zoom_percent = zoom.clamp(MIN_GRID_ZOOM_PERCENT, MAX_GRID_ZOOM_PERCENT);
+            }
+            if let Ok(layouts) = hkcu.get_value::<String, _>("WindowLayouts") {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&layouts) {
+                    settings.window_layouts = Self::parse_window_layouts(&json);
+                }
             }
         }
 
@@ -166,6 +281,12 @@ impl AppSettings {
         key.set_value("CsvEncoding", &self.csv_encoding.as_str())
             .map_err(|e| format!("Failed to save CsvEncoding: {}", e))?;
 
+        key.set_value("CsvDelimiter", &(self.csv_delimiter as u32))
+            .map_err(|e| format!("Failed to save CsvDelimiter: {}", e))?;
+
+        key.set_value("CsvExportMode", &self.csv_export_mode.as_str())
+            .map_err(|e| format!("Failed to save CsvExportMode: {}", e))?;
+
         key.set_value("AutoSaveEnabled", &(self.auto_save_enabled as u32))
             .map_err(|e| format!("Failed to save AutoSaveEnabled: {}", e))?;
 
@@ -175,40 +296,102 @@ impl AppSettings {
         key.set_value("AeKeyframeVersion", &self.ae_keyframe_version.as_str())
             .map_err(|e| format!("Failed to save AeKeyframeVersion: {}", e))?;
 
+        key.set_value("NewFramerate", &self.new_framerate)
+            .map_err(|e| format!("Failed to save NewFramerate: {}", e))?;
+
+        key.set_value("NewLayerCount", &(self.new_layer_count as u32))
+            .map_err(|e| format!("Failed to save NewLayerCount: {}", e))?;
+
+        key.set_value("NewFramesPerPage", &self.new_frames_per_page)
+            .map_err(|e| format!("Failed to save NewFramesPerPage: {}", e))?;
+
+        key.set_value("NewSeconds", &self.new_seconds)
+            .map_err(|e| format!("Failed to save NewSeconds: {}", e))?;
+
+        key.set_value("NewFrames", &self.new_frames)
+            .map_err(|e| format!("Failed to save NewFrames: {}", e))?;
+
+        key.set_value("GridZoomPercent", &self.grid_zoom_percent)
+            .map_err(|e| format!("Failed to save GridZoomPercent: {}", e))?;
+
+        key.set_value("WindowLayouts", &self.window_layouts_json().to_string())
+            .map_err(|e| format!("Failed to save WindowLayouts: {}", e))?;
+
         Ok(())
     }
 
-    // ========== macOS/Linux: File-based storage ==========
+    // ========== macOS/Linux (or no registry): File-based storage ==========
 
     /// Get config file path for non-Windows platforms
-    #[cfg(all(not(windows), feature = "dirs"))]
+    #[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
     fn config_file_path() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join(APP_NAME).join(CONFIG_FILE_NAME))
     }
 
     /// Load settings from config file (macOS/Linux)
-    #[cfg(all(not(windows), feature = "dirs"))]
+    #[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
     pub fn load_from_registry() -> Self {
+        Self::config_file_path()
+            .map(|config_path| Self::load_from_file(&config_path))
+            .unwrap_or_default()
+    }
+
+    /// Save settings to config file (macOS/Linux)
+    #[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
+    pub fn save_to_registry(&self) -> Result<(), String> {
+        let config_path = Self::config_file_path()
+            .ok_or_else(|| "Failed to get config directory".to_string())?;
+        self.save_to_file(&config_path)
+    }
+
+    /// Load settings from a JSON file; missing or invalid fields keep their defaults
+    #[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
+    fn load_from_file(config_path: &Path) -> Self {
         let mut settings = Self::default();
 
-        if let Some(config_path) = Self::config_file_path() {
-            if let Ok(content) = fs::read_to_string(&config_path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(header_name) = json.get("csv_header_name").and_then(|v| v.as_str()) {
-                        settings.csv_header_name = header_name.to_string();
-                    }
-                    if let Some(encoding) = json.get("csv_encoding").and_then(|v| v.as_str()) {
-                        settings.csv_encoding = CsvEncoding::from_str(encoding);
-                    }
-                    if let Some(auto_save) = json.get("auto_save_enabled").and_then(|v| v.as_bool()) {
-                        settings.auto_save_enabled = auto_save;
-                    }
-                    if let Some(theme) = json.get("theme_mode").and_then(|v| v.as_str()) {
-                        settings.theme_mode = ThemeMode::from_str(theme);
-                    }
-                    if let Some(ae_version) = json.get("ae_keyframe_version").and_then(|v| v.as_str()) {
-                        settings.ae_keyframe_version = AeKeyframeVersion::from_str(ae_version);
-                    }
+        if let Ok(content) = fs::read_to_string(config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(header_name) = json.get("csv_header_name").and_then(|v| v.as_str()) {
+                    settings.csv_header_name = header_name.to_string();
+                }
+                if let Some(encoding) = json.get("csv_encoding").and_then(|v| v.as_str()) {
+                    settings.csv_encoding = CsvEncoding::from_label(encoding);
+                }
+                if let Some(delimiter) = json.get("csv_delimiter").and_then(|v| v.as_u64()) {
+                    settings.csv_delimiter = delimiter as u8;
+                }
+                if let Some(mode) = json.get("csv_export_mode").and_then(|v| v.as_str()) {
+                    settings.csv_export_mode = CsvExportMode::from_label(mode);
+                }
+                if let Some(auto_save) = json.get("auto_save_enabled").and_then(|v| v.as_bool()) {
+                    settings.auto_save_enabled = auto_save;
+                }
+                if let Some(theme) = json.get("theme_mode").and_then(|v| v.as_str()) {
+                    settings.theme_mode = ThemeMode::from_label(theme);
+                }
+                if let Some(ae_version) = json.get("ae_keyframe_version").and_then(|v| v.as_str()) {
+                    settings.ae_keyframe_version = AeKeyframeVersion::from_label(ae_version);
+                }
+                if let Some(framerate) = json.get("new_framerate").and_then(|v| v.as_u64()).filter(|&v| v > 0) {
+                    settings.new_framerate = framerate as u32;
+                }
+                if let Some(layer_count) = json.get("new_layer_count").and_then(|v| v.as_u64()).filter(|&v| v > 0) {
+                    settings.new_layer_count = layer_count as usize;
+                }
+                if let Some(frames_per_page) = json.get("new_frames_per_page").and_then(|v| v.as_u64()).filter(|&v| v > 0) {
+                    settings.new_frames_per_page = frames_per_page as u32;
+                }
+                if let Some(seconds) = json.get("new_seconds").and_then(|v| v.as_u64()) {
+                    settings.new_seconds = seconds as u32;
+                }
+                if let Some(frames) = json.get("new_frames").and_then(|v| v.as_u64()) {
+                    settings.new_frames = frames as u32;
+                }
+                if let Some(zoom) = json.get("grid_zoom_percent").and_then(|v| v.as_u64()) {
+                    settings.grid_zoom_percent = (zoom.min(u32::MAX as u64) as u32).clamp(MIN_GRID_ZOOM_PERCENT, MAX_GRID_ZOOM_PERCENT);
+                }
+                if let Some(layouts) = json.get("window_layouts") {
+                    settings.window_layouts = Self::parse_window_layouts(layouts);
                 }
             }
         }
@@ -216,12 +399,9 @@ impl AppSettings {
         settings
     }
 
-    /// Save settings to config file (macOS/Linux)
-    #[cfg(all(not(windows), feature = "dirs"))]
-    pub fn save_to_registry(&self) -> Result<(), String> {
-        let config_path = Self::config_file_path()
-            .ok_or_else(|| "Failed to get config directory".to_string())?;
-
+    /// Save settings to a JSON file, creating the parent directory if needed
+    #[cfg(all(not(all(windows, feature = "winreg")), feature = "dirs"))]
+    fn save_to_file(&self, config_path: &Path) -> Result<(), String> {
         // Create config directory if it doesn't exist
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)
@@ -231,15 +411,24 @@ impl AppSettings {
         let json = serde_json::json!({
             "csv_header_name": self.csv_header_name,
             "csv_encoding": self.csv_encoding.as_str(),
+            "csv_delimiter": self.csv_delimiter,
+            "csv_export_mode": self.csv_export_mode.as_str(),
             "auto_save_enabled": self.auto_save_enabled,
             "theme_mode": self.theme_mode.as_str(),
-            "ae_keyframe_version": self.ae_keyframe_version.as_str()
+            "ae_keyframe_version": self.ae_keyframe_version.as_str(),
+            "new_framerate": self.new_framerate,
+            "new_layer_count": self.new_layer_count,
+            "new_frames_per_page": self.new_frames_per_page,
+            "new_seconds": self.new_seconds,
+            "new_frames": self.new_frames,
+            "grid_zoom_percent": self.grid_zoom_percent,
+            "window_layouts": self.window_layouts_json()
         });
 
         let content = serde_json::to_string_pretty(&json)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&config_path, content)
+        fs::write(config_path, content)
             .map_err(|e| format!("Failed to write config file: {}", e))?;
 
         Ok(())
@@ -248,13 +437,13 @@ impl AppSettings {
     // ========== Fallback: No persistent storage ==========
 
     /// Load settings (fallback when no storage feature is enabled)
-    #[cfg(not(any(all(windows, feature = "winreg"), all(not(windows), feature = "dirs"))))]
+    #[cfg(not(any(all(windows, feature = "winreg"), feature = "dirs")))]
     pub fn load_from_registry() -> Self {
         Self::default()
     }
 
     /// Save settings (fallback when no storage feature is enabled)
-    #[cfg(not(any(all(windows, feature = "winreg"), all(not(windows), feature = "dirs"))))]
+    #[cfg(not(any(all(windows, feature = "winreg"), feature = "dirs")))]
     pub fn save_to_registry(&self) -> Result<(), String> {
         Ok(())
     }
@@ -262,3 +451,79 @@ impl AppSettings {
 
 // Keep ExportSettings as alias for backward compatibility
 pub type ExportSettings = AppSettings;
+
+#[cfg(all(test, not(all(windows, feature = "winreg")), feature = "dirs"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sub").join(CONFIG_FILE_NAME);
+
+        let settings = AppSettings {
+            csv_header_name: "原画".to_string(),
+            csv_encoding: CsvEncoding::ShiftJis,
+            csv_delimiter: b';',
+            csv_export_mode: CsvExportMode::FullFrames,
+            auto_save_enabled: true,
+            theme_mode: ThemeMode::Dark,
+            ae_keyframe_version: AeKeyframeVersion::V7,
+            new_framerate: 30,
+            new_layer_count: 8,
+            new_frames_per_page: 96,
+            new_seconds: 3,
+            new_frames: 12,
+            grid_zoom_percent: 150,
+            window_layouts: vec![("/tmp/a.sts".to_string(), [10.0, 20.0, 640.0, 480.0])],
+        };
+        settings.save_to_file(&path).unwrap();
+
+        let loaded = AppSettings::load_from_file(&path);
+        assert_eq!(loaded.csv_header_name, settings.csv_header_name);
+        assert_eq!(loaded.csv_encoding, settings.csv_encoding);
+        assert_eq!(loaded.csv_delimiter, settings.csv_delimiter);
+        assert_eq!(loaded.csv_export_mode, settings.csv_export_mode);
+        assert_eq!(loaded.auto_save_enabled, settings.auto_save_enabled);
+        assert_eq!(loaded.theme_mode, settings.theme_mode);
+        assert_eq!(loaded.ae_keyframe_version, settings.ae_keyframe_version);
+        assert_eq!(loaded.new_framerate, settings.new_framerate);
+        assert_eq!(loaded.new_layer_count, settings.new_layer_count);
+        assert_eq!(loaded.new_frames_per_page, settings.new_frames_per_page);
+        assert_eq!(loaded.new_seconds, settings.new_seconds);
+        assert_eq!(loaded.new_frames, settings.new_frames);
+        assert_eq!(loaded.grid_zoom_percent, settings.grid_zoom_percent);
+        assert_eq!(loaded.window_layouts, settings.window_layouts);
+
+        // 文件不存在时使用默认值
+        let missing = AppSettings::load_from_file(&dir.path().join("missing.json"));
+        assert_eq!(missing.csv_header_name, AppSettings::default().csv_header_name);
+    }
+
+    #[test]
+    fn test_remember_window_layout() {
+        let mut settings = AppSettings::default();
+        settings.remember_window_layout("a.sts", [0.0, 0.0, 800.0, 600.0]);
+        settings.remember_window_layout("b.sts", [10.0, 10.0, 500.0, 400.0]);
+        settings.remember_window_layout("a.sts", [50.0, 60.0, 700.0, 500.0]);
+
+        // 同一文件只保留最新的布局，并移到最前
+        assert_eq!(settings.window_layouts.len(), 2);
+        assert_eq!(settings.window_layouts[0].0, "a.sts");
+        assert_eq!(settings.window_layout("a.sts"), Some([50.0, 60.0, 700.0, 500.0]));
+        assert_eq!(settings.window_layout("c.sts"), None);
+
+        settings.remember_window_layout("nan.sts", [f32::NAN, 0.0, 1.0, 1.0]);
+        assert_eq!(settings.window_layout("nan.sts"), None);
+
+        for i in 0..MAX_WINDOW_LAYOUTS {
+            settings.remember_window_layout(&format!("{}.sts", i), [0.0; 4]);
+        }
+        assert_eq!(settings.window_layouts.len(), MAX_WINDOW_LAYOUTS);
+        assert_eq!(settings.window_layout("b.sts"), None);
+
+        // 无效条目在读取时跳过
+        let json = serde_json::json!([{ "path": "x.sts", "rect": [1, 2, 3] }, { "path": "y.sts", "rect": [1, 2, 3, 4] }]);
+        assert_eq!(AppSettings::parse_window_layouts(&json), vec![("y.sts".to_string(), [1.0, 2.0, 3.0, 4.0])]);
+    }
+}
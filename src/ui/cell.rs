@@ -1,11 +1,25 @@
 //! Cell rendering module
 
+use std::borrow::Cow;
+
 use eframe::egui;
 use crate::document::Document;
 use sts_rust::models::timesheet::CellValue;
+use sts_rust::TimeSheet;
 
 pub const DASH: &str = "-";
 
+// 100% 缩放时的表格尺寸（逻辑像素），实际尺寸乘以设置中的缩放比例
+pub const BASE_ROW_HEIGHT: f32 = 16.0;
+pub const BASE_COL_WIDTH: f32 = 36.0;
+pub const BASE_FONT_SIZE: f32 = 11.0;
+
+/// 按行高缩放字号
+#[inline]
+pub fn font_size_for_row(row_height: f32) -> f32 {
+    BASE_FONT_SIZE * row_height / BASE_ROW_HEIGHT
+}
+
 /// Theme-aware colors for cells
 pub struct CellColors {
     pub bg_editing: egui::Color32,
@@ -15,12 +29,16 @@ pub struct CellColors {
     pub border_selection: egui::Color32,
     pub border_normal: egui::Color32,
     pub text_color: egui::Color32,
+    // 显式输入的 Same（"-"）使用较暗的颜色，与重复数字形成的 "-" 区分
+    pub same_text: egui::Color32,
     // Header colors
     pub header_bg: egui::Color32,
     pub header_bg_editing: egui::Color32,
     pub header_text: egui::Color32,
     // Frame number column colors
     pub frame_col_text: egui::Color32,
+    // 帧号列中每秒的刻度线和秒数
+    pub second_tick: egui::Color32,
 }
 
 impl CellColors {
@@ -35,10 +53,12 @@ impl CellColors {
                 border_selection: egui::Color32::from_rgb(100, 150, 255),
                 border_normal: egui::Color32::from_rgb(80, 80, 80),
                 text_color: egui::Color32::from_rgb(220, 220, 220),
+                same_text: egui::Color32::from_rgb(130, 130, 130),
                 header_bg: egui::Color32::from_rgb(50, 50, 50),
                 header_bg_editing: egui::Color32::from_rgb(80, 80, 50),
                 header_text: egui::Color32::from_rgb(200, 200, 200),
                 frame_col_text: egui::Color32::from_rgb(150, 150, 150),
+                second_tick: egui::Color32::from_rgb(230, 160, 70),
             }
         } else {
             // Light theme colors
@@ -50,15 +70,66 @@ impl CellColors {
                 border_selection: egui::Color32::from_rgb(100, 150, 255),
                 border_normal: egui::Color32::GRAY,
                 text_color: egui::Color32::BLACK,
+                same_text: egui::Color32::from_rgb(150, 150, 150),
                 header_bg: egui::Color32::from_rgb(240, 240, 240),
                 header_bg_editing: egui::Color32::from_rgb(255, 255, 200),
                 header_text: egui::Color32::BLACK,
                 frame_col_text: egui::Color32::DARK_GRAY,
+                second_tick: egui::Color32::from_rgb(210, 110, 0),
             }
         }
     }
 }
 
+/// 按编号着色时的背景色：由编号散列出色相，同一编号始终得到相同颜色
+pub fn number_color(n: u32, dark_mode: bool) -> egui::Color32 {
+    // 黄金分割比步进色相，相邻编号的颜色差异明显
+    let hue = (n as f32 * 0.618_034).fract();
+    let (saturation, value) = if dark_mode { (0.5, 0.45) } else { (0.35, 0.95) };
+    let [r, g, b] = egui::ecolor::rgb_from_hsv((hue, saturation, value)).map(|c| (c * 255.0).round() as u8);
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// 在给定背景上保持可读的文字颜色
+pub fn contrast_text(bg: egui::Color32) -> egui::Color32 {
+    let luma = 0.299 * bg.r() as f32 + 0.587 * bg.g() as f32 + 0.114 * bg.b() as f32;
+    if luma > 140.0 { egui::Color32::BLACK } else { egui::Color32::WHITE }
+}
+
+/// 单元格显示的文本和颜色，空格返回 None
+/// 与上一格相同的值显示为 "-"，显式输入的 Same 使用较暗的颜色
+/// 纯数字借用 num_buf，只有带后缀的编号（如 "12A"）才分配字符串
+pub fn cell_display<'a>(
+    timesheet: &TimeSheet,
+    layer_idx: usize,
+    frame_idx: usize,
+    num_buf: &'a mut itoa::Buffer,
+    colors: &CellColors,
+) -> Option<(Cow<'a, str>, egui::Color32)> {
+    let current_val = timesheet.get_cell(layer_idx, frame_idx)?;
+    let should_show_dash = frame_idx > 0 &&
+        timesheet.get_cell(layer_idx, frame_idx - 1)
+            .map_or(false, |prev| current_val == prev);
+
+    let display_text = if should_show_dash {
+        Cow::Borrowed(DASH)
+    } else {
+        match current_val {
+            CellValue::Number(n) => Cow::Borrowed(num_buf.format(*n)),
+            CellValue::Labeled(..) => Cow::Owned(current_val.to_string()),
+            CellValue::Same => Cow::Borrowed(DASH),
+        }
+    };
+
+    let text_color = if *current_val == CellValue::Same {
+        colors.same_text
+    } else {
+        colors.text_color
+    };
+
+    Some((display_text, text_color))
+}
+
 /// 渲染单个单元格
 /// `can_start_drag`: 是否允许开始新的拖拽（防止多窗口同时拖拽）
 /// 返回值：是否开始了新的拖拽
@@ -93,11 +164,19 @@ pub fn render_cell(
 
     let is_in_selection = doc.is_cell_in_selection(layer_idx, frame_idx);
 
+    // 按编号着色：仅替换普通状态的背景，选中 / 编辑状态仍使用主题颜色
+    let number_bg = if doc.color_by_number && !is_editing && !is_selected && !is_in_selection {
+        doc.timesheet.get_actual_value(layer_idx, frame_idx)
+            .map(|n| number_color(n, ui.visuals().dark_mode))
+    } else {
+        None
+    };
+
     // 合并背景和边框绘制调用
     let bg_color = if is_editing { colors.bg_editing }
         else if is_selected { colors.bg_selected }
         else if is_in_selection { colors.bg_in_selection }
-        else { colors.bg_normal };
+        else { number_bg.unwrap_or(colors.bg_normal) };
 
     let border_color = if is_in_selection { colors.border_selection } else { colors.border_normal };
 
@@ -121,27 +200,15 @@ pub fn render_cell(
             doc.finish_edit(false, true);
         }
     } else {
-        if let Some(current_val) = doc.timesheet.get_cell(layer_idx, frame_idx) {
-            let should_show_dash = frame_idx > 0 &&
-                doc.timesheet.get_cell(layer_idx, frame_idx - 1)
-                    .map_or(false, |prev| current_val == prev);
-
-            let mut num_buf = itoa::Buffer::new();
-            let display_text = if should_show_dash {
-                DASH
-            } else {
-                match current_val {
-                    CellValue::Number(n) => num_buf.format(*n),
-                    CellValue::Same => DASH,
-                }
-            };
-
+        let mut num_buf = itoa::Buffer::new();
+        if let Some((display_text, text_color)) = cell_display(&doc.timesheet, layer_idx, frame_idx, &mut num_buf, colors) {
+            let text_color = number_bg.map_or(text_color, contrast_text);
             ui.painter().text(
                 cell_rect.center(),
                 egui::Align2::CENTER_CENTER,
                 display_text,
-                egui::FontId::monospace(11.0),
-                colors.text_color,
+                egui::FontId::monospace(font_size_for_row(row_height)),
+                text_color,
             );
         }
     }
@@ -201,3 +268,22 @@ pub fn render_cell(
 
     started_drag
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_color() {
+        for dark_mode in [false, true] {
+            // 同一编号颜色稳定，循环中的不同编号颜色不同
+            assert_eq!(number_color(3, dark_mode), number_color(3, dark_mode));
+            let cycle: Vec<_> = (1..=6).map(|n| number_color(n, dark_mode)).collect();
+            for (i, a) in cycle.iter().enumerate() {
+                assert!(cycle[i + 1..].iter().all(|b| a != b));
+            }
+        }
+        assert_eq!(contrast_text(number_color(1, false)), egui::Color32::BLACK);
+        assert_eq!(contrast_text(number_color(1, true)), egui::Color32::WHITE);
+    }
+}
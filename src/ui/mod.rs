@@ -2,6 +2,7 @@
 
 pub mod cell;
 pub mod about;
+pub mod snapshot;
 
 pub use cell::{render_cell, CellColors};
 pub use about::AboutDialog;
@@ -0,0 +1,248 @@
+//! Copy as Image - rasterizes a cell range (frame column + layers) to RGBA
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use eframe::egui;
+use sts_rust::TimeSheet;
+use crate::ui::cell::{cell_display, CellColors, BASE_COL_WIDTH, BASE_FONT_SIZE, BASE_ROW_HEIGHT};
+
+// 与 100% 缩放时的表格使用相同的尺寸（逻辑像素）
+const ROW_HEIGHT: f32 = BASE_ROW_HEIGHT;
+const COL_WIDTH: f32 = BASE_COL_WIDTH;
+const PAGE_COL_WIDTH: f32 = BASE_COL_WIDTH;
+const FONT_SIZE: f32 = BASE_FONT_SIZE;
+const TEXT_PADDING: f32 = 3.0;
+// 输出图片放大倍数，粘贴到聊天工具中更清晰
+const IMAGE_SCALE: f32 = 2.0;
+// 图片最大边长（像素），避免整表选区生成超大图片
+const MAX_IMAGE_SIDE: usize = 16384;
+
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// 按字体族顺序查找包含字符的字体（与 egui 的字体回退一致）
+struct GlyphFonts<'a> {
+    fonts: Vec<FontRef<'a>>,
+}
+
+impl<'a> GlyphFonts<'a> {
+    fn new(definitions: &'a egui::FontDefinitions, family: &egui::FontFamily) -> Self {
+        let fonts = definitions.families.get(family)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| definitions.font_data.get(name))
+            .filter_map(|data| FontRef::try_from_slice_and_index(&data.font, data.index).ok())
+            .collect();
+        Self { fonts }
+    }
+
+    fn font_for(&self, c: char) -> Option<&FontRef<'a>> {
+        self.fonts.iter()
+            .find(|font| font.glyph_id(c).0 != 0)
+            .or_else(|| self.fonts.first())
+    }
+}
+
+struct Canvas {
+    image: image::RgbaImage,
+}
+
+impl Canvas {
+    fn fill_rect(&mut self, rect: egui::Rect, color: egui::Color32) {
+        let (x0, y0, x1, y1) = self.pixel_bounds(rect);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.image.put_pixel(x, y, image::Rgba(color.to_array()));
+            }
+        }
+    }
+
+    fn stroke_rect(&mut self, rect: egui::Rect, color: egui::Color32) {
+        let (x0, y0, x1, y1) = self.pixel_bounds(rect);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let px = image::Rgba(color.to_array());
+        for x in x0..x1 {
+            self.image.put_pixel(x, y0, px);
+            self.image.put_pixel(x, y1 - 1, px);
+        }
+        for y in y0..y1 {
+            self.image.put_pixel(x0, y, px);
+            self.image.put_pixel(x1 - 1, y, px);
+        }
+    }
+
+    fn pixel_bounds(&self, rect: egui::Rect) -> (u32, u32, u32, u32) {
+        let to_px = |v: f32, max: u32| ((v * IMAGE_SCALE).round().max(0.0) as u32).min(max);
+        (
+            to_px(rect.min.x, self.image.width()),
+            to_px(rect.min.y, self.image.height()),
+            to_px(rect.max.x, self.image.width()),
+            to_px(rect.max.y, self.image.height()),
+        )
+    }
+
+    /// 在 rect 内垂直居中绘制单行文本
+    fn text(&mut self, fonts: &GlyphFonts, rect: egui::Rect, align: Align, text: &str, color: egui::Color32) {
+        let scale = PxScale::from(FONT_SIZE * IMAGE_SCALE);
+
+        // 先排版：计算每个字形的字体和水平位置
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut caret = 0.0;
+        let mut max_ascent: f32 = 0.0;
+        let mut max_descent: f32 = 0.0;
+        for c in text.chars() {
+            let Some(font) = fonts.font_for(c) else {
+                continue;
+            };
+            let scaled = font.as_scaled(scale);
+            let glyph_id = font.glyph_id(c);
+            max_ascent = max_ascent.max(scaled.ascent());
+            max_descent = max_descent.min(scaled.descent());
+            glyphs.push((font, glyph_id, caret));
+            caret += scaled.h_advance(glyph_id);
+        }
+
+        let left = rect.min.x * IMAGE_SCALE;
+        let right = rect.max.x * IMAGE_SCALE;
+        let padding = TEXT_PADDING * IMAGE_SCALE;
+        let start_x = match align {
+            Align::Left => left + padding,
+            Align::Center => (left + right - caret) / 2.0,
+            Align::Right => right - padding - caret,
+        };
+        let baseline = rect.center().y * IMAGE_SCALE + (max_ascent + max_descent) / 2.0;
+
+        let (width, height) = self.image.dimensions();
+        let [r, g, b, _] = color.to_array();
+        for (font, glyph_id, offset) in glyphs {
+            let glyph = glyph_id.with_scale_and_position(scale, ab_glyph::point(start_x + offset, baseline));
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let x = bounds.min.x as i64 + gx as i64;
+                let y = bounds.min.y as i64 + gy as i64;
+                // 文本不超出所在单元格
+                if x < left as i64 || x >= right as i64 || x >= width as i64 || y < 0 || y >= height as i64 {
+                    return;
+                }
+                let pixel = self.image.get_pixel_mut(x as u32, y as u32);
+                let blend = |bg: u8, fg: u8| (bg as f32 * (1.0 - coverage) + fg as f32 * coverage).round() as u8;
+                pixel.0 = [blend(pixel.0[0], r), blend(pixel.0[1], g), blend(pixel.0[2], b), 255];
+            });
+        }
+    }
+}
+
+/// 将 (min_layer, min_frame, max_layer, max_frame) 范围绘制为图片：
+/// 表头 + 帧号列 + 所选列，颜色与字形和表格显示一致
+pub fn render_range_image(
+    timesheet: &TimeSheet,
+    range: (usize, usize, usize, usize),
+    colors: &CellColors,
+    definitions: &egui::FontDefinitions,
+) -> Result<image::RgbaImage, String> {
+    let (min_layer, min_frame, max_layer, max_frame) = range;
+    if max_layer >= timesheet.layer_count || min_layer > max_layer || min_frame > max_frame {
+        return Err("Invalid selection".to_string());
+    }
+
+    let layer_count = max_layer - min_layer + 1;
+    let frame_count = max_frame - min_frame + 1;
+    let width = ((PAGE_COL_WIDTH + COL_WIDTH * layer_count as f32) * IMAGE_SCALE) as usize;
+    let height = (ROW_HEIGHT * (frame_count + 1) as f32 * IMAGE_SCALE) as usize;
+    if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        return Err("Selection is too large to copy as image".to_string());
+    }
+
+    let mut canvas = Canvas {
+        image: image::RgbaImage::from_pixel(width as u32, height as u32, image::Rgba(colors.bg_normal.to_array())),
+    };
+    let mono = GlyphFonts::new(definitions, &egui::FontFamily::Monospace);
+    let proportional = GlyphFonts::new(definitions, &egui::FontFamily::Proportional);
+
+    let cell_rect = |col: f32, row: usize, w: f32| {
+        egui::Rect::from_min_size(egui::pos2(col, row as f32 * ROW_HEIGHT), egui::vec2(w, ROW_HEIGHT))
+    };
+
+    // 表头
+    for (i, layer_idx) in (min_layer..=max_layer).enumerate() {
+        let rect = cell_rect(PAGE_COL_WIDTH + COL_WIDTH * i as f32, 0, COL_WIDTH);
+        canvas.fill_rect(rect, colors.header_bg);
+        canvas.stroke_rect(rect, colors.border_normal);
+        let name = timesheet.layer_names.get(layer_idx).map(String::as_str).unwrap_or_default();
+        canvas.text(&proportional, rect, Align::Center, name, colors.header_text);
+    }
+
+    let mut page_buf = itoa::Buffer::new();
+    let mut frame_buf = itoa::Buffer::new();
+    let mut num_buf = itoa::Buffer::new();
+    for (row, frame_idx) in (min_frame..=max_frame).enumerate() {
+        let row = row + 1;
+
+        // 帧号列
+        let (page, frame_in_page) = timesheet.get_page_and_frame(frame_idx);
+        let page_rect = cell_rect(0.0, row, PAGE_COL_WIDTH);
+        canvas.stroke_rect(page_rect, colors.border_normal);
+        canvas.text(&mono, page_rect, Align::Left, page_buf.format(page), colors.frame_col_text);
+        canvas.text(&mono, page_rect, Align::Right, frame_buf.format(frame_in_page), colors.frame_col_text);
+
+        for (i, layer_idx) in (min_layer..=max_layer).enumerate() {
+            let rect = cell_rect(PAGE_COL_WIDTH + COL_WIDTH * i as f32, row, COL_WIDTH);
+            canvas.stroke_rect(rect, colors.border_normal);
+            if let Some((text, color)) = cell_display(timesheet, layer_idx, frame_idx, &mut num_buf, colors) {
+                canvas.text(&mono, rect, Align::Center, &text, color);
+            }
+        }
+    }
+
+    Ok(canvas.image)
+}
+
+/// 将图片放入系统剪贴板（arboard 会以 PNG 等图片格式提供给其他程序）
+pub fn copy_image_to_clipboard(image: &image::RgbaImage) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_image(arboard::ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sts_rust::CellValue;
+
+    #[test]
+    fn test_render_range_image() {
+        let mut ts = TimeSheet::new("test".to_string(), 24, 3, 144);
+        ts.ensure_frames(4);
+        ts.set_cell(1, 0, Some(CellValue::Number(12)));
+        ts.set_cell(1, 1, Some(CellValue::Same));
+
+        let colors = CellColors::from_visuals(&egui::Visuals::light());
+        let definitions = egui::FontDefinitions::default();
+        let image = render_range_image(&ts, (1, 0, 2, 3), &colors, &definitions).unwrap();
+
+        assert_eq!(image.width(), ((PAGE_COL_WIDTH + COL_WIDTH * 2.0) * IMAGE_SCALE) as u32);
+        assert_eq!(image.height(), (ROW_HEIGHT * 5.0 * IMAGE_SCALE) as u32);
+
+        // 第一行数据格中应有文字像素
+        let text = egui::Color32::BLACK.to_array();
+        let y0 = (ROW_HEIGHT * IMAGE_SCALE) as u32;
+        let x0 = (PAGE_COL_WIDTH * IMAGE_SCALE) as u32;
+        let has_text = (y0 + 2..y0 + (ROW_HEIGHT * IMAGE_SCALE) as u32 - 2)
+            .any(|y| (x0 + 2..x0 + (COL_WIDTH * IMAGE_SCALE) as u32 - 2).any(|x| image.get_pixel(x, y).0 == text));
+        assert!(has_text);
+
+        assert!(render_range_image(&ts, (0, 0, 3, 0), &colors, &definitions).is_err());
+    }
+}
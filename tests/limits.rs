@@ -0,0 +1,78 @@
+//! 所有解析器共用同一帧数上限（含运行时覆盖）
+//!
+//! 修改的是进程级全局设置，因此放在独立的集成测试进程中运行。
+
+use sts_rust::formats::sxf::{LayerData, LayerGroup};
+use sts_rust::limits::{max_frames, set_max_frames, MAX_FRAMES};
+use sts_rust::{formats, CellValue, StsError, TimeSheet};
+
+const FRAMES: usize = 11;
+
+#[test]
+fn test_parsers_share_frame_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let mut ts = TimeSheet::new("limit".to_string(), 24, 1, 144);
+    ts.ensure_frames(FRAMES);
+    ts.set_cell(0, 0, Some(CellValue::Number(1)));
+    ts.set_cell(0, FRAMES - 1, Some(CellValue::Number(2)));
+
+    formats::write_sts_file(&ts, &path("a.sts")).unwrap();
+    formats::write_csv_file(&ts, &path("a.csv")).unwrap();
+    formats::write_xdts_file(&ts, &path("a.xdts")).unwrap();
+    formats::write_tdts_file(&ts, "limit", &path("a.tdts")).unwrap();
+    formats::write_json_file(&ts, &path("a.json")).unwrap();
+    formats::write_ae_keyframe_file(&ts, 0, "9.0", &path("a.txt")).unwrap();
+    let groups = vec![LayerGroup {
+        name: "原画".to_string(),
+        layers: vec![LayerData { name: "A".to_string(), frames: vec!["1".to_string(); FRAMES] }],
+    }];
+    formats::write_sxf_binary(&groups, &path("a.sxf")).unwrap();
+
+    let parse_all = || -> Vec<(&str, sts_rust::error::Result<()>)> {
+        vec![
+            ("sts", formats::parse_sts_file(&path("a.sts")).map(drop)),
+            ("csv", formats::parse_csv_file(&path("a.csv")).map(drop)),
+            ("xdts", formats::parse_xdts_file(&path("a.xdts")).map(drop)),
+            ("tdts", formats::parse_tdts_file(&path("a.tdts")).map(drop)),
+            ("json", formats::parse_json_file(&path("a.json")).map(drop)),
+            ("txt", formats::parse_ae_keyframe_file(&path("a.txt")).map(drop)),
+            ("sxf", formats::parse_sxf_groups(&path("a.sxf")).map(drop)),
+            ("sxf groups", formats::groups_to_timesheet(&groups, "a.sxf").map(drop)),
+            ("builder", sts_rust::TimeSheetBuilder::new().layer("A", &[]).frames(FRAMES).build().map(drop)),
+        ]
+    };
+
+    assert_eq!(max_frames(), MAX_FRAMES);
+    for (format, result) in parse_all() {
+        assert!(result.is_ok(), "{} should load under the default limit: {:?}", format, result);
+    }
+
+    // 上限低于文件帧数时每种格式都拒绝
+    set_max_frames(FRAMES - 1);
+    for (format, result) in parse_all() {
+        assert!(
+            matches!(result, Err(StsError::TooManyFrames { found: FRAMES, max }) if max == FRAMES - 1),
+            "{} should reject {} frames: {:?}",
+            format,
+            FRAMES,
+            result
+        );
+    }
+
+    // 恰好等于上限时允许
+    set_max_frames(FRAMES);
+    for (format, result) in parse_all() {
+        assert!(result.is_ok(), "{} should load at the limit: {:?}", format, result);
+    }
+
+    // set_cell 自动扩展同样受上限约束
+    let mut ts = TimeSheet::new("extend".to_string(), 24, 1, 144);
+    ts.set_cell(0, FRAMES, Some(CellValue::Number(1)));
+    assert_eq!(ts.total_frames(), 0);
+
+    set_max_frames(MAX_FRAMES);
+    ts.set_cell(0, 20_000, Some(CellValue::Number(1)));
+    assert_eq!(ts.total_frames(), 20_001);
+}